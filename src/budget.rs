@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 超出的预算类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetExhausted {
+    /// 请求数达到--max
+    Requests,
+    /// 总下载字节数达到--max-bytes
+    Bytes,
+    /// 单个主机下载字节数达到--max-host-bytes
+    HostBytes,
+    /// 运行时间达到--max-time
+    Time,
+}
+
+impl fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            BudgetExhausted::Requests => "request budget exhausted (--max)",
+            BudgetExhausted::Bytes => "byte budget exhausted (--max-bytes)",
+            BudgetExhausted::HostBytes => "host byte budget exhausted (--max-host-bytes)",
+            BudgetExhausted::Time => "time budget exhausted (--max-time)",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// 已用的请求数和字节数，保存在快照中以便继续扫描时接着计算
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    pub requests: usize,
+    pub bytes: u64,
+    pub host_bytes: HashMap<String, u64>,
+}
+
+/// 扫描的请求数、字节数和时间预算
+#[derive(Debug)]
+pub struct Budget {
+    max_requests: Option<usize>,
+    max_bytes: Option<u64>,
+    max_host_bytes: Option<u64>,
+    deadline: Option<Instant>,
+    requests: AtomicUsize,
+    bytes: AtomicU64,
+    host_bytes: Mutex<HashMap<String, u64>>,
+}
+
+impl Budget {
+    pub fn new(
+        max_requests: Option<usize>,
+        max_bytes: Option<u64>,
+        max_host_bytes: Option<u64>,
+        max_time: Option<Duration>,
+    ) -> Self {
+        Budget {
+            max_requests,
+            max_bytes,
+            max_host_bytes,
+            deadline: max_time.map(|t| Instant::now() + t),
+            requests: AtomicUsize::new(0),
+            bytes: AtomicU64::new(0),
+            host_bytes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 预占一次请求，任一预算已用完时返回对应原因
+    pub fn try_request(&self, host: &str) -> Result<(), BudgetExhausted> {
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(BudgetExhausted::Time);
+        }
+        if self.max_bytes.is_some_and(|max| self.bytes() >= max) {
+            return Err(BudgetExhausted::Bytes);
+        }
+        if let Some(max) = self.max_host_bytes {
+            let host_bytes = self.host_bytes.lock().map_err(|_| BudgetExhausted::HostBytes)?;
+            if host_bytes.get(host).is_some_and(|&used| used >= max) {
+                return Err(BudgetExhausted::HostBytes);
+            }
+        }
+
+        let reserved = self.requests.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+            match self.max_requests {
+                Some(max) if count >= max => None,
+                _ => Some(count + 1),
+            }
+        });
+        reserved.map(|_| ()).map_err(|_| BudgetExhausted::Requests)
+    }
+
+    /// 记录下载的响应字节数
+    pub fn add_bytes(&self, host: &str, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::SeqCst);
+        if let Ok(mut host_bytes) = self.host_bytes.lock() {
+            *host_bytes.entry(host.to_string()).or_insert(0) += bytes;
+        }
+    }
+
+    /// 导出已用的预算
+    pub fn usage(&self) -> BudgetUsage {
+        BudgetUsage {
+            requests: self.requests(),
+            bytes: self.bytes(),
+            host_bytes: self.host_bytes.lock().map(|host_bytes| host_bytes.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// 从快照恢复已用的预算
+    pub fn restore(&self, usage: BudgetUsage) {
+        self.requests.store(usage.requests, Ordering::SeqCst);
+        self.bytes.store(usage.bytes, Ordering::SeqCst);
+        if let Ok(mut host_bytes) = self.host_bytes.lock() {
+            *host_bytes = usage.host_bytes;
+        }
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::SeqCst)
+    }
+
+    /// 下载字节数达到单主机预算的主机
+    pub fn exhausted_hosts(&self) -> Vec<String> {
+        let Some(max) = self.max_host_bytes else {
+            return Vec::new();
+        };
+        let mut hosts: Vec<String> = self.host_bytes.lock()
+            .map(|host_bytes| {
+                host_bytes.iter()
+                    .filter(|(_, &used)| used >= max)
+                    .map(|(host, _)| host.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        hosts.sort();
+        hosts
+    }
+}
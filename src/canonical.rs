@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// URL规范化规则，用于去重
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanonicalConfig {
+    /// 去掉#片段
+    #[serde(default = "default_true")]
+    pub strip_fragment: bool,
+    /// 按参数名排序查询参数
+    #[serde(default = "default_true")]
+    pub sort_query: bool,
+    /// 去掉跟踪参数
+    #[serde(default)]
+    pub drop_tracking_params: bool,
+    /// 跟踪参数名，以*结尾表示前缀匹配
+    #[serde(default = "default_tracking_params")]
+    pub tracking_params: Vec<String>,
+}
+
+impl Default for CanonicalConfig {
+    fn default() -> Self {
+        CanonicalConfig {
+            strip_fragment: true,
+            sort_query: true,
+            drop_tracking_params: false,
+            tracking_params: default_tracking_params(),
+        }
+    }
+}
+
+fn default_true() -> bool { true }
+
+fn default_tracking_params() -> Vec<String> {
+    vec![
+        "utm_*".to_string(),
+        "fbclid".to_string(),
+        "gclid".to_string(),
+        "msclkid".to_string(),
+        "spm".to_string(),
+        "_ga".to_string(),
+    ]
+}
+
+/// 生成URL的规范形式: 协议和主机小写、去掉默认端口，并按配置处理片段和查询参数
+pub fn canonicalize(url: &str, config: &CanonicalConfig) -> String {
+    // url库解析时已将协议和主机转为小写，并去掉了默认端口
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+
+    if config.strip_fragment {
+        parsed.set_fragment(None);
+    }
+
+    if let Some(query) = parsed.query() {
+        if query.is_empty() {
+            parsed.set_query(None);
+        } else if config.sort_query || config.drop_tracking_params {
+            let mut pairs: Vec<(String, String)> = parsed.query_pairs().into_owned().collect();
+            if config.drop_tracking_params {
+                pairs.retain(|(name, _)| !is_tracking_param(name, &config.tracking_params));
+            }
+            if config.sort_query {
+                pairs.sort();
+            }
+            if pairs.is_empty() {
+                parsed.set_query(None);
+            } else {
+                parsed.query_pairs_mut().clear().extend_pairs(pairs);
+            }
+        }
+    }
+
+    parsed.to_string()
+}
+
+fn is_tracking_param(name: &str, tracking_params: &[String]) -> bool {
+    let name = name.to_ascii_lowercase();
+    tracking_params.iter().any(|param| match param.strip_suffix('*') {
+        Some(prefix) => name.starts_with(&prefix.to_ascii_lowercase()),
+        None => name == param.to_ascii_lowercase(),
+    })
+}
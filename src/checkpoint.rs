@@ -0,0 +1,138 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::budget::BudgetUsage;
+use crate::canonical::CanonicalConfig;
+use crate::frontier::FrontierEntry;
+use crate::output::OutputResult;
+use crate::scope::ScopeConfig;
+use crate::state::StateSnapshot;
+
+/// 扫描进度快照，用于中断后继续扫描
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// 已访问、JS、Fuzz和范围外的URL集合
+    #[serde(flatten)]
+    pub state: StateSnapshot,
+    /// 尚未完成的抓取任务，包括保存时正在进行中的请求
+    pub pending: Vec<FrontierEntry>,
+    /// 已收集的结果
+    pub results: Vec<OutputResult>,
+    /// 保存时的扫描选项，旧版本的快照中没有
+    #[serde(default)]
+    pub options: Option<ScanOptions>,
+    /// 已用的请求数和字节数
+    #[serde(default)]
+    pub usage: BudgetUsage,
+}
+
+/// 影响扫描过程的选项，继续扫描时需与保存快照时一致
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanOptions {
+    pub mode: u8,
+    pub fuzz_mode: Option<u8>,
+    pub base_url: Option<String>,
+    pub url_depth: u8,
+    pub js_depth: u8,
+    /// 扫描范围，包括-d指定的域名
+    pub scope: ScopeConfig,
+    pub canonical: CanonicalConfig,
+}
+
+impl ScanOptions {
+    /// 与other不同的选项
+    pub fn diff(&self, other: &ScanOptions) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.mode != other.mode {
+            changed.push("-m");
+        }
+        if self.fuzz_mode != other.fuzz_mode {
+            changed.push("-z");
+        }
+        if self.base_url != other.base_url {
+            changed.push("-b");
+        }
+        if self.url_depth != other.url_depth || self.js_depth != other.js_depth {
+            changed.push("url_depth/js_depth");
+        }
+        if self.scope != other.scope {
+            changed.push("-d/scope");
+        }
+        if self.canonical != other.canonical {
+            changed.push("canonical");
+        }
+        changed
+    }
+}
+
+impl Checkpoint {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let checkpoint = serde_json::from_str(&content)?;
+        Ok(checkpoint)
+    }
+
+    /// 先写入临时文件再重命名，避免中断时留下不完整的快照
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+/// 按固定间隔保存快照
+pub struct Checkpointer {
+    path: PathBuf,
+    interval: Duration,
+    last_saved: Instant,
+    options: ScanOptions,
+}
+
+impl Checkpointer {
+    pub fn new(path: PathBuf, interval: Duration, options: ScanOptions) -> Self {
+        Checkpointer {
+            path,
+            interval,
+            last_saved: Instant::now(),
+            options,
+        }
+    }
+
+    /// 距上次保存是否已超过间隔
+    pub fn is_due(&self) -> bool {
+        self.last_saved.elapsed() >= self.interval
+    }
+
+    pub fn options(&self) -> &ScanOptions {
+        &self.options
+    }
+
+    /// 在阻塞线程中序列化并写入快照，完成后交还快照和保存结果
+    pub async fn save(&mut self, checkpoint: Checkpoint) -> (Checkpoint, Result<()>) {
+        let path = self.path.clone();
+        let saved = tokio::task::spawn_blocking(move || {
+            let result = checkpoint.save(&path);
+            (checkpoint, result)
+        }).await;
+        let (checkpoint, result) = match saved {
+            Ok(saved) => saved,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        };
+        if result.is_ok() {
+            self.last_saved = Instant::now();
+        }
+        (checkpoint, result)
+    }
+
+    /// 扫描正常结束后删除快照
+    pub fn remove(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
@@ -13,8 +13,12 @@ impl CliRunner {
 
     pub fn validate(&self) -> Result<()> {
         // 检查必要的参数
-        if self.cli.url.is_none() && self.cli.file.is_none() && self.cli.unified_file.is_none() {
-            anyhow::bail!("必须指定目标URL(-u)或URL文件(-f/-ff)")
+        if self.cli.url.is_none()
+            && self.cli.file.is_none()
+            && self.cli.unified_file.is_none()
+            && self.cli.resume.is_none()
+        {
+            anyhow::bail!("必须指定目标URL(-u)、URL文件(-f/-ff)或快照文件(--resume)")
         }
 
         // 检查模式参数
@@ -29,6 +33,18 @@ impl CliRunner {
             }
         }
 
+        // 检查CPU线程数
+        if self.cli.cpu_threads == Some(0) {
+            anyhow::bail!("CPU线程数(--cpu-threads)必须大于0")
+        }
+
+        // 检查限速参数
+        if let Some(rate) = self.cli.rate {
+            if !rate.is_finite() || rate < 0.0 {
+                anyhow::bail!("每秒请求数(--rate)必须为非负数")
+            }
+        }
+
         Ok(())
     }
 
@@ -67,6 +83,10 @@ impl CliRunner {
         self.cli.threads
     }
 
+    pub fn get_cpu_threads(&self) -> Option<usize> {
+        self.cli.cpu_threads
+    }
+
     pub fn get_timeout(&self) -> u64 {
         self.cli.timeout
     }
@@ -79,6 +99,18 @@ impl CliRunner {
         self.cli.max_count
     }
 
+    pub fn get_max_bytes(&self) -> Option<u64> {
+        self.cli.max_bytes
+    }
+
+    pub fn get_max_host_bytes(&self) -> Option<u64> {
+        self.cli.max_host_bytes
+    }
+
+    pub fn get_max_time(&self) -> Option<u64> {
+        self.cli.max_time
+    }
+
     pub fn get_status_filter(&self) -> Option<String> {
         self.cli.status.clone()
     }
@@ -106,4 +138,33 @@ impl CliRunner {
     pub fn get_fuzz_mode(&self) -> Option<u8> {
         self.cli.fuzz
     }
+
+    pub fn get_host_threads(&self) -> Option<usize> {
+        self.cli.host_threads
+    }
+
+    pub fn get_rate(&self) -> Option<f64> {
+        self.cli.rate
+    }
+
+    pub fn get_delay(&self) -> Option<u64> {
+        self.cli.delay
+    }
+
+    pub fn get_jitter(&self) -> Option<u64> {
+        self.cli.jitter
+    }
+
+    pub fn get_resume_path(&self) -> Option<PathBuf> {
+        self.cli.resume.clone()
+    }
+
+    pub fn get_checkpoint_path(&self) -> Option<PathBuf> {
+        // 继续扫描时默认写回原快照文件
+        self.cli.checkpoint.clone().or_else(|| self.cli.resume.clone())
+    }
+
+    pub fn get_checkpoint_interval(&self) -> u64 {
+        self.cli.checkpoint_interval
+    }
 }
\ No newline at end of file
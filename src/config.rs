@@ -3,7 +3,10 @@ use std::path::PathBuf;
 use std::fs;
 use anyhow::Result;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::canonical::CanonicalConfig;
+use crate::scope::ScopeConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// 请求头配置
     #[serde(default)]
@@ -35,9 +38,21 @@ pub struct Config {
     /// JS Fuzz路径
     #[serde(default)]
     pub js_fuzz_paths: Vec<String>,
+    /// 请求频率限制
+    #[serde(default)]
+    pub rate_limit: RateLimit,
+    /// 请求重试策略
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// 扫描范围
+    #[serde(default)]
+    pub scope: ScopeConfig,
+    /// URL去重规范化规则
+    #[serde(default)]
+    pub canonical: CanonicalConfig,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Headers {
     #[serde(default)]
     pub user_agent: String,
@@ -51,8 +66,62 @@ pub struct Headers {
     pub accept_encoding: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RateLimit {
+    /// 单个主机的最大并发请求数(0为不限制)
+    #[serde(default)]
+    pub host_concurrency: usize,
+    /// 单个主机每秒最大请求数(0为不限制)
+    #[serde(default)]
+    pub requests_per_second: f64,
+    /// 每次请求前的固定延迟(毫秒)
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// 每次请求前额外的随机延迟上限(毫秒)
+    #[serde(default)]
+    pub jitter_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// 最大重试次数
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// 初始退避时间(毫秒)，每次重试翻倍
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+    /// 最大退避时间(毫秒)，同时限制Retry-After的等待时间
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// 需要重试的网络错误类型: timeout, connect, dns, reset
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<String>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: default_max_retries(),
+            backoff_ms: default_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            retry_on: default_retry_on(),
+        }
+    }
+}
+
 fn default_url_depth() -> u8 { 1 }
 fn default_js_depth() -> u8 { 3 }
+fn default_max_retries() -> u32 { 3 }
+fn default_backoff_ms() -> u64 { 1000 }
+fn default_max_backoff_ms() -> u64 { 60000 }
+fn default_retry_on() -> Vec<String> {
+    vec![
+        "timeout".to_string(),
+        "connect".to_string(),
+        "dns".to_string(),
+        "reset".to_string(),
+    ]
+}
 
 impl Config {
     pub fn load(path: &PathBuf) -> Result<Self> {
@@ -105,6 +174,10 @@ impl Config {
                 "app.js".to_string(),
                 "index.js".to_string(),
             ],
+            rate_limit: RateLimit::default(),
+            retry: RetryPolicy::default(),
+            scope: ScopeConfig::default(),
+            canonical: CanonicalConfig::default(),
         }
     }
 }
\ No newline at end of file
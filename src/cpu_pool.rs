@@ -0,0 +1,45 @@
+use anyhow::{anyhow, Context, Result};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use tokio::sync::oneshot;
+
+/// 执行内容提取等CPU密集任务的线程池，避免阻塞异步运行时的工作线程
+#[derive(Debug)]
+pub struct CpuPool {
+    pool: rayon::ThreadPool,
+}
+
+impl CpuPool {
+    /// threads为None时使用CPU核数
+    pub fn new(threads: Option<usize>) -> Result<Self> {
+        let mut builder = rayon::ThreadPoolBuilder::new()
+            .thread_name(|i| format!("extract-{}", i));
+        if let Some(threads) = threads {
+            builder = builder.num_threads(threads);
+        }
+        let pool = builder.build().context("无法创建CPU线程池")?;
+        Ok(CpuPool { pool })
+    }
+
+    /// 在线程池中执行任务并等待结果，任务panic时返回错误
+    pub async fn run<F, R>(&self, task: F) -> Result<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.pool.spawn(move || {
+            let _ = tx.send(catch_unwind(AssertUnwindSafe(task)));
+        });
+        rx.await
+            .context("内容提取任务异常退出")?
+            .map_err(|payload| anyhow!("内容提取任务panic: {}", panic_message(payload.as_ref())))
+    }
+}
+
+/// panic时传入的消息，通常为&str或String
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload.downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown")
+}
@@ -1,11 +1,17 @@
 use anyhow::Result;
-use regex::Regex;
 use reqwest::{Client, ClientBuilder};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Semaphore;
 
-use crate::utils::{normalize_url, is_domain_match, is_status_match};
+use crate::utils::is_status_match;
+use crate::budget::Budget;
 use crate::config::Config;
+use crate::cpu_pool::CpuPool;
+use crate::extract::{extract, ExtractOptions, Extracted, Link};
+use crate::rate_limit::HostLimiter;
+use crate::rules::RuleEngine;
+use crate::retry::{backoff_delay, classify_error, is_retryable, parse_retry_after};
 use crate::Cli;
 use crate::utils;
 use clap::Parser;
@@ -15,18 +21,30 @@ pub struct Crawler {
     client: Client,
     config: Arc<Config>,
     semaphore: Arc<Semaphore>,
+    limiter: Arc<HostLimiter>,
+    budget: Arc<Budget>,
+    rules: Arc<RuleEngine>,
+    cpu_pool: Arc<CpuPool>,
     cli: Cli,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CrawlResult {
     pub url: String,
     pub status: u16,
     pub content_type: String,
-    pub urls: Vec<String>,
-    pub js_urls: Vec<String>,
+    pub urls: Vec<Link>,
+    pub js_urls: Vec<Link>,
     pub sensitive_info: Vec<String>,
-    pub source: String,
+    pub sources: Vec<String>,
+    pub routes: Vec<String>,
+}
+
+/// 一次请求的响应内容
+struct Fetched {
+    status: u16,
+    content_type: String,
+    body: String,
 }
 
 impl Crawler {
@@ -38,16 +56,18 @@ impl Crawler {
         self.cli.fuzz
     }
 
-    pub fn crawled_count(&self) -> usize {
-        0 // TODO: Implement crawled count tracking
+    pub fn budget(&self) -> &Budget {
+        &self.budget
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: Config,
         threads: usize,
+        cpu_threads: Option<usize>,
         timeout: u64,
         _mode: u8,
-        _max_count: Option<usize>,
+        budget: Arc<Budget>,
         _base_url: Option<String>,
         user_agent: Option<String>,
         cookie: Option<String>,
@@ -84,55 +104,21 @@ impl Crawler {
 
         Ok(Crawler {
             client,
+            limiter: Arc::new(HostLimiter::new(config.rate_limit.clone(), threads)),
+            budget,
+            rules: Arc::new(RuleEngine::compile(&config)?),
+            cpu_pool: Arc::new(CpuPool::new(cpu_threads)?),
             config: Arc::new(config),
             semaphore: Arc::new(Semaphore::new(threads)),
             cli: Cli::parse(),
         })
     }
 
-    pub async fn crawl(&self, target_url: &str, _depth: u8) -> Result<CrawlResult> {
+    pub async fn crawl(&self, target_url: &str) -> Result<CrawlResult> {
         let mode = self.get_mode();
         let _fuzz_mode = self.get_fuzz_mode();
 
-        // 检查域名是否匹配
-        if let Some(domain) = &self.cli.domain {
-            if !is_domain_match(target_url, domain) {
-                return Ok(CrawlResult {
-                    url: target_url.to_string(),
-                    status: 0,
-                    content_type: String::new(),
-                    urls: Vec::new(),
-                    js_urls: Vec::new(),
-                    sensitive_info: Vec::new(),
-                    source: String::new(),
-                });
-            }
-        }
-
-        // 检查是否达到最大爬取数量
-        if let Some(max_count) = self.cli.max_count {
-            if self.crawled_count() >= max_count {
-                return Ok(CrawlResult {
-                    url: target_url.to_string(),
-                    status: 0,
-                    content_type: String::new(),
-                    urls: Vec::new(),
-                    js_urls: Vec::new(),
-                    sensitive_info: Vec::new(),
-                    source: String::new(),
-                });
-            }
-        }
-        let _permit = self.semaphore.acquire().await?;
-
-        let resp = self.client.get(target_url).send().await?;
-        let status = resp.status().as_u16();
-        let content_type = resp.headers()
-            .get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("").to_string();
-
-        let content = resp.text().await?;
+        let Fetched { status, content_type, body: content } = self.fetch(target_url).await?;
 
         // 检查状态码是否匹配
         if let Some(status_str) = &self.cli.status {
@@ -144,99 +130,30 @@ impl Crawler {
                     urls: Vec::new(),
                     js_urls: Vec::new(),
                     sensitive_info: Vec::new(),
-                    source: content,
+                    sources: Vec::new(),
+                    routes: Vec::new(),
                 });
             }
         }
 
-        let mut urls = Vec::new();
-        let mut js_urls = Vec::new();
-        let mut sensitive_info = Vec::new();
-
-        // 根据模式处理URL和敏感信息
-        match mode {
-            // 正常模式：只处理页面中的URL
-            1 => {
-                for pattern in &self.config.url_patterns {
-                    let re = Regex::new(pattern)?;
-                    for cap in re.captures_iter(&content) {
-                        if let Some(url) = cap.get(0) {
-                            let url = url.as_str().to_string();
-                            if !self.is_filtered(&url) {
-                                urls.push(self.normalize_url(&url, target_url)?);    
-                            }
-                        }
-                    }
-                }
-            }
-            // 深入模式：处理页面URL和JS中的URL
-            2 => {
-                // 处理页面URL
-                for pattern in &self.config.url_patterns {
-                    let re = Regex::new(pattern)?;
-                    for cap in re.captures_iter(&content) {
-                        if let Some(url) = cap.get(0) {
-                            let url = url.as_str().to_string();
-                            if !self.is_filtered(&url) {
-                                urls.push(self.normalize_url(&url, target_url)?);    
-                            }
-                        }
-                    }
-                }
-
-                // 处理JS URL
-                for pattern in &self.config.js_patterns {
-                    let re = Regex::new(pattern)?;
-                    for cap in re.captures_iter(&content) {
-                        if let Some(url) = cap.get(0) {
-                            let url = url.as_str().to_string();
-                            if !self.is_js_filtered(&url) {
-                                js_urls.push(self.normalize_url(&url, target_url)?);    
-                            }
-                        }
-                    }
-                }
-            }
-            // 安全深入模式：处理页面URL、JS URL和敏感信息
-            3 => {
-                // 处理页面URL
-                for pattern in &self.config.url_patterns {
-                    let re = Regex::new(pattern)?;
-                    for cap in re.captures_iter(&content) {
-                        if let Some(url) = cap.get(0) {
-                            let url = url.as_str().to_string();
-                            if !self.is_filtered(&url) {
-                                urls.push(self.normalize_url(&url, target_url)?);    
-                            }
-                        }
-                    }
-                }
-
-                // 处理JS URL
-                for pattern in &self.config.js_patterns {
-                    let re = Regex::new(pattern)?;
-                    for cap in re.captures_iter(&content) {
-                        if let Some(url) = cap.get(0) {
-                            let url = url.as_str().to_string();
-                            if !self.is_js_filtered(&url) {
-                                js_urls.push(self.normalize_url(&url, target_url)?);    
-                            }
-                        }
-                    }
-                }
-
-                // 处理敏感信息
-                for pattern in &self.config.sensitive_patterns {
-                    let re = Regex::new(pattern)?;
-                    for cap in re.captures_iter(&content) {
-                        if let Some(info) = cap.get(0) {
-                            sensitive_info.push(info.as_str().to_string());
-                        }
-                    }
-                }
-            }
-            _ => {}
-        }
+        // 正则提取在CPU线程池中执行，不阻塞网络请求
+        let rules = self.rules.clone();
+        let options = ExtractOptions {
+            mode,
+            base_override: self.cli.base_url.clone(),
+            probe_source_map: self.cli.sourcemap_probe,
+            decode_base64: self.cli.decode_base64,
+        };
+        let page_url = target_url.to_string();
+        let declared_type = content_type.clone();
+        let extracted = self.cpu_pool.run(move || {
+            extract(&rules, &options, &declared_type, &page_url, &content)
+        }).await;
+        // 提取失败不影响已获取的响应，按没有提取到内容处理
+        let Extracted { urls, js_urls, sensitive_info, sources, routes } = extracted.unwrap_or_else(|e| {
+            eprintln!("Error extracting {}: {}", target_url, e);
+            Extracted::default()
+        });
 
         Ok(CrawlResult {
             url: target_url.to_string(),
@@ -245,39 +162,79 @@ impl Crawler {
             urls,
             js_urls,
             sensitive_info,
-            source: content,
+            sources,
+            routes,
         })
     }
 
-    fn is_filtered(&self, url: &str) -> bool {
-        self.config.url_filters.iter().any(|pattern| {
-            if let Ok(re) = Regex::new(pattern) {
-                re.is_match(url)
-            } else {
-                false
+    /// 请求URL，遇到429/503或可重试的网络错误时按Retry-After或指数退避重试
+    async fn fetch(&self, target_url: &str) -> Result<Fetched> {
+        let host = utils::host_of(target_url);
+        let retry = &self.config.retry;
+        let mut attempt = 0;
+        let mut backoff = None;
+        // 每次重试同样计入请求预算，预算用完后不再重试
+        let can_retry = |attempt: u32| attempt < retry.max_retries && self.budget.try_request(&host).is_ok();
+
+        loop {
+            // 网络错误后的退避，不占用并发名额
+            if let Some(wait) = backoff.take() {
+                tokio::time::sleep(wait).await;
             }
-        })
-    }
 
-    fn is_js_filtered(&self, url: &str) -> bool {
-        self.config.js_filters.iter().any(|pattern| {
-            if let Ok(re) = Regex::new(pattern) {
-                re.is_match(url)
+            // 先等待主机限速，避免占用全局并发名额
+            let _host_permit = self.limiter.acquire(&host).await?;
+            let _permit = self.semaphore.acquire().await?;
+
+            let resp = match self.client.get(target_url).send().await {
+                Ok(resp) => resp,
+                Err(e) if is_retryable(retry, &e) && can_retry(attempt) => {
+                    backoff = Some(backoff_delay(retry, attempt));
+                    attempt += 1;
+                    continue;
+                }
+                Err(e) => return Err(network_error(e, attempt)),
+            };
+            let status = resp.status().as_u16();
+
+            if matches!(status, 429 | 503) {
+                if can_retry(attempt) {
+                    let wait = resp.headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(parse_retry_after)
+                        .map(|wait| wait.min(Duration::from_millis(retry.max_backoff_ms)))
+                        .unwrap_or_else(|| backoff_delay(retry, attempt));
+                    self.limiter.throttle(&host, wait).await;
+                    attempt += 1;
+                    continue;
+                }
             } else {
-                false
+                self.limiter.recover(&host).await;
             }
-        })
-    }
 
-    fn normalize_url(&self, url: &str, base_url: &str) -> Result<String> {
-        // 如果设置了基础URL，优先使用基础URL
-        let effective_base = if let Some(base) = &self.cli.base_url {
-            base
-        } else {
-            base_url
-        };
-        normalize_url(url, effective_base)
-    }
+            let content_type = resp.headers()
+                .get("content-type")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("").to_string();
+            let body = match resp.text().await {
+                Ok(body) => body,
+                Err(e) if is_retryable(retry, &e) && can_retry(attempt) => {
+                    backoff = Some(backoff_delay(retry, attempt));
+                    attempt += 1;
+                    continue;
+                }
+                Err(e) => return Err(network_error(e, attempt)),
+            };
+            self.budget.add_bytes(&host, body.len() as u64);
 
+            return Ok(Fetched { status, content_type, body });
+        }
+    }
+}
 
-}
\ No newline at end of file
+/// 为最终失败的网络错误附加错误类型和重试次数
+fn network_error(err: reqwest::Error, retries: u32) -> anyhow::Error {
+    let kind = classify_error(&err).unwrap_or("request");
+    anyhow::Error::new(err).context(format!("{} error after {} retries", kind, retries))
+}
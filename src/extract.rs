@@ -0,0 +1,509 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::rules::RuleEngine;
+use crate::utils::normalize_url;
+
+mod css;
+mod decode;
+mod html;
+mod js;
+mod json;
+mod lexer;
+mod manifest;
+mod sourcemap;
+mod state;
+mod xml;
+
+/// 产生URL的提取器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Extractor {
+    Html,
+    Js,
+    Json,
+    Xml,
+    Css,
+    Text,
+    SourceMap,
+    /// HTML中内嵌的应用状态
+    State,
+    /// 框架的构建清单
+    #[serde(rename = "nextjs")]
+    Next,
+    Nuxt,
+    Vite,
+}
+
+impl fmt::Display for Extractor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Extractor::Html => "html",
+            Extractor::Js => "js",
+            Extractor::Json => "json",
+            Extractor::Xml => "xml",
+            Extractor::Css => "css",
+            Extractor::Text => "text",
+            Extractor::SourceMap => "sourcemap",
+            Extractor::State => "state",
+            Extractor::Next => "nextjs",
+            Extractor::Nuxt => "nuxt",
+            Extractor::Vite => "vite",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 提取出的URL及其来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Link {
+    pub url: String,
+    pub source: Extractor,
+    /// 代码中能确定的请求方法
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// 从脚本中推断出的基础地址，如axios的baseURL或webpack的publicPath
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// 在内嵌状态数据中的JSON路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 地址中含有无法确定的表达式，只报告不请求
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unresolved: bool,
+}
+
+/// 随URL一起记录的附加信息
+#[derive(Debug, Default, Clone, Copy)]
+struct Meta<'m> {
+    method: Option<&'m str>,
+    base: Option<&'m str>,
+    path: Option<&'m str>,
+    unresolved: bool,
+}
+
+/// 从响应内容中提取的结果
+#[derive(Debug, Default)]
+pub struct Extracted {
+    pub urls: Vec<Link>,
+    pub js_urls: Vec<Link>,
+    pub sensitive_info: Vec<String>,
+    /// 从source map中还原出的源文件路径
+    pub sources: Vec<String>,
+    /// 前端路由中的页面路径
+    pub routes: Vec<String>,
+}
+
+/// 提取选项
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    pub mode: u8,
+    /// -b指定的基础地址
+    pub base_override: Option<String>,
+    /// JS中没有sourceMappingURL时是否探测<file>.map
+    pub probe_source_map: bool,
+    /// 是否解码文本中的Base64片段
+    pub decode_base64: bool,
+}
+
+/// 按内容类型选择提取器，二进制内容直接跳过
+pub fn extract(
+    rules: &RuleEngine,
+    options: &ExtractOptions,
+    content_type: &str,
+    url: &str,
+    content: &str,
+) -> Extracted {
+    let Some(extractor) = detect(content_type, url, content) else {
+        return Extracted::default();
+    };
+
+    // 相对地址默认相对于页面URL解析，-b指定时固定使用该地址
+    let base_override = options.base_override.as_deref();
+    let mut collector = Collector {
+        rules,
+        mode: options.mode,
+        page_url: url,
+        base_url: base_override.unwrap_or(url).to_string(),
+        base_fixed: base_override.is_some(),
+        base_forced: base_override.is_some(),
+        decode_base64: options.decode_base64,
+        located: HashSet::new(),
+        extracted: Extracted::default(),
+    };
+    match extractor {
+        Extractor::Html => html::extract(&mut collector, content),
+        Extractor::Js => {
+            js::extract(&mut collector, content);
+            if options.probe_source_map && js::source_mapping_url(content).is_none() {
+                let path = url.split(['?', '#']).next().unwrap_or(url);
+                collector.push_asset(&format!("{}.map", path), Extractor::Js);
+            }
+        }
+        Extractor::Json => json::extract(&mut collector, content),
+        Extractor::Xml => xml::extract(&mut collector, content),
+        Extractor::Css => css::extract(&mut collector, content),
+        Extractor::Text => collector.scan(content, extractor),
+        Extractor::SourceMap => sourcemap::extract(&mut collector, content),
+        Extractor::State => state::json(&mut collector, "$", content),
+        Extractor::Next => manifest::next(&mut collector, content),
+        Extractor::Nuxt => manifest::nuxt(&mut collector, content),
+        Extractor::Vite => manifest::vite(&mut collector, content),
+    }
+
+    // source map按其中的原始代码匹配敏感信息
+    if extractor != Extractor::SourceMap {
+        collector.scan_sensitive(content);
+    }
+    collector.extracted
+}
+
+/// 提取器共用的URL收集，负责按模式匹配、过滤和解析相对地址
+pub(crate) struct Collector<'a> {
+    rules: &'a RuleEngine,
+    mode: u8,
+    page_url: &'a str,
+    base_url: String,
+    base_fixed: bool,
+    /// 是否通过-b指定了基础地址，指定时不使用脚本中推断的基础地址
+    base_forced: bool,
+    decode_base64: bool,
+    /// 已连同JSON路径记录的敏感信息，整体匹配时不再重复记录
+    located: HashSet<String>,
+    extracted: Extracted,
+}
+
+impl Collector<'_> {
+    /// 用URL和JS规则匹配一段文本
+    pub(crate) fn scan(&mut self, text: &str, source: Extractor) {
+        self.scan_at(text, source, None);
+    }
+
+    /// 同scan，path为文本在JSON中的位置
+    pub(crate) fn scan_at(&mut self, text: &str, source: Extractor, path: Option<&str>) {
+        let text = self.decode(text);
+        self.scan_decoded(&text, source, path);
+    }
+
+    /// 同scan_at，text已还原过转义和编码，不再重复解码
+    pub(crate) fn scan_decoded(&mut self, text: &str, source: Extractor, path: Option<&str>) {
+        let rules = self.rules;
+        let meta = Meta { path, ..Meta::default() };
+        for url in rules.url_patterns.find_iter(text) {
+            self.push(url, source, false, meta);
+        }
+        if self.mode >= 2 {
+            for url in rules.js_patterns.find_iter(text) {
+                self.push(url, source, true, meta);
+            }
+        }
+    }
+
+    /// 使用文档中的<base href>解析相对地址，-b优先，且只有第一个生效
+    pub(crate) fn set_base(&mut self, href: &str) {
+        if self.base_fixed || href.trim().is_empty() {
+            return;
+        }
+        if let Ok(base) = normalize_url(href, self.page_url) {
+            self.base_url = base;
+            self.base_fixed = true;
+        }
+    }
+
+    pub(crate) fn base_forced(&self) -> bool {
+        self.base_forced
+    }
+
+    /// 安全深入模式：匹配敏感信息
+    pub(crate) fn scan_sensitive(&mut self, text: &str) {
+        self.scan_sensitive_at(text, None);
+    }
+
+    /// 同scan_sensitive，path为文本在JSON中的位置，记录为"匹配内容 @ 路径"
+    pub(crate) fn scan_sensitive_at(&mut self, text: &str, path: Option<&str>) {
+        if self.mode != 3 {
+            return;
+        }
+        let text = self.decode(text);
+        for found in self.rules.sensitive_patterns.find_iter(&text) {
+            let key = sensitive_key(found);
+            match path {
+                Some(path) => {
+                    self.located.insert(key);
+                    self.extracted.sensitive_info.push(format!("{} @ {}", found, path));
+                }
+                None if self.located.contains(&key) => {}
+                None => self.extracted.sensitive_info.push(found.to_string()),
+            }
+        }
+    }
+
+    /// 匹配规则前还原转义和编码
+    pub(crate) fn decode<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        decode::decode(text, self.decode_base64)
+    }
+
+    pub(crate) fn page_url(&self) -> &str {
+        self.page_url
+    }
+
+    /// 按当前基础地址解析相对地址
+    pub(crate) fn resolve(&self, url: &str) -> Option<String> {
+        normalize_url(url, &self.base_url).ok()
+    }
+
+    /// 添加source map、构建清单等附属文件，按JS抓取；地址相对于当前文件而非<base href>
+    pub(crate) fn push_asset(&mut self, url: &str, source: Extractor) {
+        if let Ok(url) = normalize_url(url, self.page_url) {
+            self.push_js_url(&url, source);
+        }
+    }
+
+    /// 记录前端路由中的页面路径，与接口地址分开输出
+    pub(crate) fn push_route(&mut self, path: String) {
+        self.extracted.routes.push(show_unknown(&path).into_owned());
+    }
+
+    /// 记录从source map中还原出的源文件路径
+    pub(crate) fn push_source(&mut self, path: String) {
+        self.extracted.sources.push(path);
+    }
+
+    /// 添加页面URL
+    pub(crate) fn push_url(&mut self, url: &str, source: Extractor) {
+        self.push(url, source, false, Meta::default());
+    }
+
+    /// 添加JS URL，深入模式及以上才提取
+    pub(crate) fn push_js_url(&mut self, url: &str, source: Extractor) {
+        self.push(url, source, true, Meta::default());
+    }
+
+    /// 添加脚本中发现的地址，按路径后缀区分JS和接口，base为拼接地址时使用的基础地址
+    pub(crate) fn push_endpoint(&mut self, url: &str, method: Option<&str>, base: Option<&str>) {
+        let meta = Meta { method, base, path: None, unresolved: url.contains(UNKNOWN) };
+        let url = show_unknown(url);
+        self.push(&url, Extractor::Js, is_js_path(&url), meta);
+    }
+
+    fn push(&mut self, url: &str, source: Extractor, js: bool, meta: Meta) {
+        let filtered = if js {
+            self.mode < 2 || self.rules.js_filters.is_match(url)
+        } else {
+            self.rules.url_filters.is_match(url)
+        };
+        if filtered {
+            return;
+        }
+        let Ok(url) = normalize_url(url, &self.base_url) else {
+            return;
+        };
+
+        let link = Link {
+            url,
+            source,
+            method: meta.method.map(String::from),
+            base: meta.base.map(|base| normalize_url(base, &self.base_url).unwrap_or_else(|_| base.to_string())),
+            path: meta.path.map(String::from),
+            unresolved: meta.unresolved,
+        };
+        if js {
+            self.extracted.js_urls.push(link);
+        } else {
+            self.extracted.urls.push(link);
+        }
+    }
+}
+
+/// 脚本求值时无法确定的表达式，用不会出现在地址中的字符标记
+pub(crate) const UNKNOWN: &str = "\0";
+
+/// 将无法确定的表达式替换为输出用的占位符
+pub(crate) fn show_unknown(text: &str) -> Cow<'_, str> {
+    if text.contains(UNKNOWN) {
+        Cow::Owned(text.replace(UNKNOWN, "EXPR"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// 忽略引号和空白比较敏感信息，"key":"v"与key: "v"视为相同
+fn sensitive_key(found: &str) -> String {
+    found.chars().filter(|c| !c.is_whitespace() && *c != '"' && *c != '\'').collect()
+}
+
+/// 路径是否以.js或.mjs结尾
+pub(crate) fn is_js_path(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or("").to_ascii_lowercase();
+    path.ends_with(".js") || path.ends_with(".mjs")
+}
+
+/// 路径是否以.map结尾
+pub(crate) fn is_source_map_path(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or("").to_ascii_lowercase();
+    path.ends_with(".map")
+}
+
+/// 还原HTML/XML中常见的字符实体
+pub(crate) fn unescape_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "sol" => Some('/'),
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// 判断内容类型: 优先使用响应头，响应头缺失或与内容明显不符时按内容和URL后缀猜测，
+/// 返回None表示二进制内容
+fn detect(content_type: &str, url: &str, content: &str) -> Option<Extractor> {
+    if let Some(framework) = manifest::detect(url, content) {
+        return Some(framework);
+    }
+    let extractor = match from_header(content_type) {
+        Some(Declared::Binary) if looks_binary(content) => None,
+        Some(Declared::Text(extractor)) if agrees(extractor, content) => Some(extractor),
+        _ => sniff(url, content),
+    };
+    match extractor {
+        Some(Extractor::Json) if sourcemap::is_source_map(content) => Some(Extractor::SourceMap),
+        extractor => extractor,
+    }
+}
+
+/// 响应头声明的类型
+enum Declared {
+    Text(Extractor),
+    Binary,
+}
+
+fn from_header(content_type: &str) -> Option<Declared> {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    let (kind, subtype) = mime.split_once('/')?;
+
+    let extractor = match (kind, subtype) {
+        // 无法确定的类型交给内容判断
+        ("text", "plain") | ("application", "octet-stream") => return None,
+        (_, "html" | "xhtml+xml") => Extractor::Html,
+        (_, "javascript" | "ecmascript" | "x-javascript" | "jsx") => Extractor::Js,
+        (_, "json") => Extractor::Json,
+        (_, s) if s.ends_with("+json") => Extractor::Json,
+        (_, "xml") => Extractor::Xml,
+        (_, s) if s.ends_with("+xml") => Extractor::Xml,
+        ("text", "css") => Extractor::Css,
+        ("text", _) => Extractor::Text,
+        ("image" | "audio" | "video" | "font", _) => return Some(Declared::Binary),
+        ("application", s) if is_binary_subtype(s) => return Some(Declared::Binary),
+        _ => return None,
+    };
+    Some(Declared::Text(extractor))
+}
+
+fn is_binary_subtype(subtype: &str) -> bool {
+    matches!(
+        subtype,
+        "pdf" | "zip" | "gzip" | "x-gzip" | "x-tar" | "x-7z-compressed" | "x-rar-compressed"
+            | "wasm" | "vnd.ms-fontobject" | "x-shockwave-flash" | "msword" | "vnd.ms-excel"
+    ) || subtype.starts_with("font") || subtype.starts_with("x-font") || subtype.starts_with("vnd.openxmlformats")
+}
+
+/// 内容是否与声明的类型相符
+fn agrees(extractor: Extractor, content: &str) -> bool {
+    let head = content.trim_start_matches('\u{feff}').trim_start();
+    match extractor {
+        Extractor::Html | Extractor::Xml => head.starts_with('<'),
+        Extractor::Json => head.starts_with('{') || head.starts_with('['),
+        _ => !looks_binary(content),
+    }
+}
+
+/// 按内容特征和URL后缀猜测类型
+fn sniff(url: &str, content: &str) -> Option<Extractor> {
+    if looks_binary(content) {
+        return None;
+    }
+
+    let head = content.trim_start_matches('\u{feff}').trim_start();
+    if head.starts_with('<') {
+        let prefix: String = head.chars().take(64).collect::<String>().to_ascii_lowercase();
+        let is_xml = ["<?xml", "<rss", "<feed", "<urlset", "<sitemapindex", "<svg"]
+            .iter()
+            .any(|tag| prefix.starts_with(tag));
+        return Some(if is_xml && !prefix.contains("<html") { Extractor::Xml } else { Extractor::Html });
+    }
+    if (head.starts_with('{') || head.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(head).is_ok()
+    {
+        return Some(Extractor::Json);
+    }
+
+    let path = url.split(['?', '#']).next().unwrap_or("").to_ascii_lowercase();
+    let extractor = if path.ends_with(".js") || path.ends_with(".mjs") {
+        Extractor::Js
+    } else if path.ends_with(".css") {
+        Extractor::Css
+    } else if path.ends_with(".json") || path.ends_with(".map") {
+        Extractor::Json
+    } else if path.ends_with(".xml") {
+        Extractor::Xml
+    } else {
+        Extractor::Text
+    };
+    Some(extractor)
+}
+
+/// 前1024个字符中出现NUL或大量无法解码的字符时视为二进制
+fn looks_binary(content: &str) -> bool {
+    let mut total = 0;
+    let mut invalid = 0;
+    for c in content.chars().take(1024) {
+        total += 1;
+        match c {
+            '\0' => return true,
+            '\u{fffd}' => invalid += 1,
+            c if c.is_control() && !c.is_whitespace() => invalid += 1,
+            _ => {}
+        }
+    }
+    total > 0 && invalid * 10 > total
+}
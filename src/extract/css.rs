@@ -0,0 +1,18 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+use super::{Collector, Extractor};
+
+/// 提取url(...)和@import引用的地址
+pub(crate) fn extract(collector: &mut Collector, content: &str) {
+    static CSS_URL: OnceLock<Regex> = OnceLock::new();
+    let re = CSS_URL.get_or_init(|| {
+        Regex::new(r#"(?i)url\(\s*['"]?([^'")\s]+)['"]?\s*\)|@import\s+['"]([^'"]+)['"]"#).expect("valid regex")
+    });
+
+    for cap in re.captures_iter(content) {
+        if let Some(url) = cap.get(1).or_else(|| cap.get(2)) {
+            collector.push_url(url.as_str(), Extractor::Css);
+        }
+    }
+}
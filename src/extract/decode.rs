@@ -0,0 +1,149 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use super::unescape_entities;
+use crate::utils::decode_base64;
+
+/// 还原压缩代码和页面中常见的编码形式: JS转义(\/、\x2f、\u002f)、HTML实体和URL编码；
+/// base64为true时追加其中能解码为文本的Base64片段
+pub(crate) fn decode(text: &str, base64: bool) -> Cow<'_, str> {
+    let mut out = Cow::Borrowed(text);
+    if out.contains('\\') {
+        out = Cow::Owned(unescape_js(&out));
+    }
+    if out.contains('&') {
+        out = Cow::Owned(unescape_entities(&out));
+    }
+    if out.contains('%') {
+        out = Cow::Owned(percent_decode(&out));
+    }
+    if base64 {
+        let decoded = base64_texts(&out);
+        if !decoded.is_empty() {
+            let mut owned = out.into_owned();
+            for text in decoded {
+                owned.push('\n');
+                owned.push_str(&text);
+            }
+            out = Cow::Owned(owned);
+        }
+    }
+    out
+}
+
+/// 还原JS字符串转义，无法识别的转义保留被转义的字符
+fn unescape_js(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let Some((_, escaped)) = chars.next() else {
+            out.push(c);
+            break;
+        };
+        // 被转义的字符可能是多字节字符
+        let rest = &text[i + 1 + escaped.len_utf8()..];
+        let (decoded, skip) = match escaped {
+            'n' => (Some('\n'), 0),
+            't' => (Some('\t'), 0),
+            'r' => (Some('\r'), 0),
+            'x' => (hex(rest, 2), 2),
+            'u' if rest.starts_with('{') => match rest.find('}') {
+                Some(end) => (u32::from_str_radix(&rest[1..end], 16).ok().and_then(char::from_u32), end + 1),
+                None => (None, 0),
+            },
+            'u' => (hex(rest, 4), 4),
+            c => (Some(c), 0),
+        };
+        match decoded {
+            Some(decoded) => {
+                out.push(decoded);
+                for _ in 0..skip {
+                    chars.next();
+                }
+            }
+            // 不完整的转义原样保留
+            None => {
+                out.push('\\');
+                out.push(escaped);
+            }
+        }
+    }
+    out
+}
+
+fn hex(text: &str, len: usize) -> Option<char> {
+    let digits = text.get(..len)?;
+    char::from_u32(u32::from_str_radix(digits, 16).ok()?)
+}
+
+/// 只还原编码后为可见ASCII字符的%XX，空格和多字节字符保持编码，避免截断或损坏地址
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut i = 0;
+    while i + 2 < bytes.len() {
+        if bytes[i] == b'%' {
+            let decoded = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .filter(|b| b.is_ascii_graphic());
+            if let Some(b) = decoded {
+                out.push_str(&text[last..i]);
+                out.push(b as char);
+                i += 3;
+                last = i;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// 文本中能解码为可读文本的Base64片段
+fn base64_texts(text: &str) -> Vec<String> {
+    static BASE64: OnceLock<Regex> = OnceLock::new();
+    let re = BASE64.get_or_init(|| Regex::new(r"[A-Za-z0-9+/]{16,}={0,2}").expect("valid regex"));
+
+    re.find_iter(text)
+        .filter(|m| m.as_str().trim_end_matches('=').len() % 4 != 1)
+        .filter_map(|m| String::from_utf8(decode_base64(m.as_str())?).ok())
+        .filter(|decoded| is_textual(decoded))
+        .collect()
+}
+
+/// 解码结果是否为文本: 没有控制字符，且大部分为可见字符
+fn is_textual(text: &str) -> bool {
+    let mut total = 0;
+    let mut printable = 0;
+    for c in text.chars() {
+        if c.is_control() && !c.is_whitespace() {
+            return false;
+        }
+        total += 1;
+        if c.is_ascii_graphic() || c == ' ' || (!c.is_ascii() && c.is_alphanumeric()) {
+            printable += 1;
+        }
+    }
+    total > 0 && printable * 10 >= total * 9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+
+    #[test]
+    fn decodes_escapes_and_encodings() {
+        assert_eq!(decode(r"C:\文件", false), "C:文件");
+        assert_eq!(decode(r"\x2fapi\x2fuser", false), "/api/user");
+        assert_eq!(decode(r"\u{2f}api", false), "/api");
+        assert_eq!(decode(r"/api\", false), r"/api\");
+        assert_eq!(decode("/api%2f", false), "/api/");
+    }
+}
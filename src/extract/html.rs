@@ -0,0 +1,280 @@
+use super::{css, is_js_path, js, manifest, state, unescape_entities, Collector, Extractor};
+
+/// 单个属性
+type Attr = (String, String);
+
+/// HTML中与链接提取有关的部分
+enum Token<'a> {
+    Tag { name: String, attrs: Vec<Attr> },
+    Script { attrs: Vec<Attr>, body: &'a str },
+    Style(&'a str),
+    Comment(&'a str),
+}
+
+/// 按标签和属性提取链接，内联脚本和样式交给对应的提取器
+pub(crate) fn extract(collector: &mut Collector, content: &str) {
+    let tokens = tokenize(content);
+
+    // <base href>对整个文档生效，以第一个为准
+    let base = tokens.iter().find_map(|token| match token {
+        Token::Tag { name, attrs } if name == "base" => attr(attrs, "href"),
+        _ => None,
+    });
+    if let Some(href) = base {
+        collector.set_base(href);
+    }
+
+    for token in &tokens {
+        match token {
+            Token::Tag { name, attrs } => extract_tag(collector, name, attrs),
+            Token::Script { attrs, body } => {
+                if let Some(src) = attr(attrs, "src") {
+                    collector.push_js_url(src, Extractor::Html);
+                    let module = attr(attrs, "type").is_some_and(|t| t.trim().eq_ignore_ascii_case("module"));
+                    manifest::discover(collector, src, module);
+                }
+                if attr(attrs, "id") == Some("__NEXT_DATA__") {
+                    manifest::next_data(collector, body);
+                }
+                // 先提取内嵌状态，同一地址保留带JSON路径的记录
+                match script_kind(attr(attrs, "type")) {
+                    ScriptKind::Js => {
+                        state::script(collector, body);
+                        js::extract(collector, body);
+                    }
+                    ScriptKind::Json => state::json(collector, attr(attrs, "id").unwrap_or("$"), body),
+                    ScriptKind::Other => {}
+                }
+            }
+            Token::Style(body) => css::extract(collector, body),
+            // 注释中常有被隐藏的接口和页面
+            Token::Comment(text) => collector.scan(text, Extractor::Html),
+        }
+    }
+}
+
+fn extract_tag(collector: &mut Collector, name: &str, attrs: &[Attr]) {
+    match name {
+        "base" => {}
+        "meta" => {
+            let is_refresh = attr(attrs, "http-equiv").is_some_and(|v| v.eq_ignore_ascii_case("refresh"));
+            if let (true, Some(content)) = (is_refresh, attr(attrs, "content")) {
+                if let Some(url) = refresh_url(content) {
+                    collector.push_url(url, Extractor::Html);
+                }
+            }
+        }
+        "link" => {
+            if let Some(href) = attr(attrs, "href") {
+                let rel = attr(attrs, "rel").unwrap_or("").to_ascii_lowercase();
+                let is_script = rel.split_whitespace().any(|r| r == "modulepreload")
+                    || attr(attrs, "as").is_some_and(|v| v.eq_ignore_ascii_case("script"));
+                if is_script || is_js_path(href) {
+                    collector.push_js_url(href, Extractor::Html);
+                    manifest::discover(collector, href, rel.split_whitespace().any(|r| r == "modulepreload"));
+                } else {
+                    collector.push_url(href, Extractor::Html);
+                }
+            }
+        }
+        _ => {
+            for (key, value) in attrs {
+                match key.as_str() {
+                    "href" | "src" | "action" | "formaction" => push_link(collector, value),
+                    "srcset" => {
+                        // 每个候选为"URL 描述符"，以逗号分隔
+                        for candidate in value.split(',') {
+                            if let Some(url) = candidate.split_whitespace().next() {
+                                push_link(collector, url);
+                            }
+                        }
+                    }
+                    key if key.starts_with("data-") && looks_like_url(value) => push_link(collector, value),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// 按路径后缀区分JS和页面链接
+fn push_link(collector: &mut Collector, url: &str) {
+    if is_js_path(url) {
+        collector.push_js_url(url, Extractor::Html);
+    } else {
+        collector.push_url(url, Extractor::Html);
+    }
+}
+
+/// data-*属性的值是否像URL
+fn looks_like_url(value: &str) -> bool {
+    let value = value.trim();
+    let lower = value.to_ascii_lowercase();
+    !value.contains(char::is_whitespace)
+        && (lower.starts_with("http://")
+            || lower.starts_with("https://")
+            || value.starts_with('/')
+            || value.starts_with("./")
+            || value.starts_with("../"))
+}
+
+/// 解析<meta http-equiv="refresh" content="5; url=/next">中的地址
+fn refresh_url(content: &str) -> Option<&str> {
+    let (_, rest) = content.split_once([';', ','])?;
+    let rest = rest.trim_start();
+    let url = match rest.get(..4) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("url=") => &rest[4..],
+        _ => rest,
+    };
+    let url = url.trim().trim_matches(['\'', '"']);
+    (!url.is_empty()).then_some(url)
+}
+
+enum ScriptKind {
+    Js,
+    Json,
+    Other,
+}
+
+/// 按<script type>判断内联脚本的类型，模板等其他类型不处理
+fn script_kind(script_type: Option<&str>) -> ScriptKind {
+    let script_type = script_type.unwrap_or("").trim().to_ascii_lowercase();
+    match script_type.as_str() {
+        "" | "module" | "text/javascript" | "application/javascript" | "text/ecmascript"
+        | "application/ecmascript" | "text/babel" | "text/jsx" => ScriptKind::Js,
+        t if t.ends_with("json") => ScriptKind::Json,
+        _ => ScriptKind::Other,
+    }
+}
+
+fn attr<'a>(attrs: &'a [Attr], name: &str) -> Option<&'a str> {
+    attrs.iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// 将HTML切分为标签、脚本、样式和注释，忽略文本内容
+fn tokenize(content: &str) -> Vec<Token<'_>> {
+    let bytes = content.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = content[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &content[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").unwrap_or(comment.len());
+            tokens.push(Token::Comment(&comment[..end]));
+            pos = start + 4 + (end + 3).min(comment.len());
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") || rest.starts_with("</") {
+            pos = start + rest.find('>').map_or(rest.len(), |end| end + 1);
+            continue;
+        }
+
+        // 标签名
+        let name_len = rest[1..]
+            .bytes()
+            .take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b':'))
+            .count();
+        if name_len == 0 || !bytes[start + 1].is_ascii_alphabetic() {
+            pos = start + 1;
+            continue;
+        }
+        let name = rest[1..=name_len].to_ascii_lowercase();
+        let (attrs, tag_end) = parse_attrs(content, start + 1 + name_len);
+        pos = tag_end;
+
+        match name.as_str() {
+            // 脚本和样式的内容为原始文本，直到对应的结束标签
+            "script" | "style" => {
+                let body_end = find_end_tag(content, pos, &name);
+                let body = &content[pos..body_end];
+                pos = content[body_end..].find('>').map_or(content.len(), |end| body_end + end + 1);
+                if name == "script" {
+                    tokens.push(Token::Script { attrs, body });
+                } else {
+                    tokens.push(Token::Style(body));
+                }
+            }
+            _ => tokens.push(Token::Tag { name, attrs }),
+        }
+    }
+
+    tokens
+}
+
+/// 从标签名之后解析属性，返回属性列表和标签结束后的位置
+fn parse_attrs(content: &str, mut pos: usize) -> (Vec<Attr>, usize) {
+    let bytes = content.as_bytes();
+    let mut attrs = Vec::new();
+
+    loop {
+        while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'/') {
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            return (attrs, pos);
+        }
+        if bytes[pos] == b'>' {
+            return (attrs, pos + 1);
+        }
+
+        let name_start = pos;
+        while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() && !matches!(bytes[pos], b'=' | b'>' | b'/') {
+            pos += 1;
+        }
+        let name = content[name_start..pos].to_ascii_lowercase();
+
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos >= bytes.len() || bytes[pos] != b'=' {
+            // 无值属性
+            if !name.is_empty() {
+                attrs.push((name, String::new()));
+            } else {
+                pos += 1;
+            }
+            continue;
+        }
+        pos += 1;
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+
+        let value = match bytes.get(pos) {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let value_start = pos + 1;
+                let value_end = content[value_start..]
+                    .find(quote as char)
+                    .map_or(content.len(), |end| value_start + end);
+                pos = (value_end + 1).min(content.len());
+                &content[value_start..value_end]
+            }
+            _ => {
+                let value_start = pos;
+                while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() && bytes[pos] != b'>' {
+                    pos += 1;
+                }
+                &content[value_start..pos]
+            }
+        };
+        attrs.push((name, unescape_entities(value)));
+    }
+}
+
+/// 查找</name的位置(不区分大小写)，找不到时返回内容末尾
+fn find_end_tag(content: &str, from: usize, name: &str) -> usize {
+    content[from..]
+        .match_indices("</")
+        .map(|(i, _)| from + i)
+        .find(|&i| {
+            content.as_bytes()
+                .get(i + 2..i + 2 + name.len())
+                .is_some_and(|tag| tag.eq_ignore_ascii_case(name.as_bytes()))
+        })
+        .unwrap_or(content.len())
+}
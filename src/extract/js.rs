@@ -0,0 +1,967 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use url::Url;
+
+use super::lexer::{pair_brackets, split_items, tokenize, Template, Token};
+use super::{is_js_path, show_unknown, sourcemap, Collector, Extractor, UNKNOWN};
+use crate::utils::decode_base64;
+
+const HTTP_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+
+/// 与path同时出现时表明对象为路由定义的属性(Vue Router、React Router、Angular)
+const ROUTE_KEYS: &[&str] = &[
+    "component", "components", "children", "redirect", "element", "Component", "lazy",
+    "loadChildren", "loadComponent", "errorElement", "redirectTo",
+];
+
+/// 不能作为表达式开头的关键字
+const RESERVED: &[&str] = &[
+    "return", "typeof", "new", "void", "delete", "in", "instanceof", "case", "throw", "yield",
+    "await", "else", "do", "of", "var", "let", "const", "function", "class", "if", "for", "while",
+];
+
+/// 按词法单元提取字符串和模板字面量，识别常见请求调用并记录请求方法，
+/// 同一文件中值为字面量的变量和字符串拼接会先求值
+pub(crate) fn extract(collector: &mut Collector, content: &str) {
+    let tokens = tokenize(content);
+    let script = Script::new(&tokens);
+    let consts = script.constants();
+    let mut bases = script.bases(&consts);
+    // -b指定的基础地址优先于脚本中推断的
+    if collector.base_forced() {
+        bases.api = None;
+        bases.public_path = None;
+    }
+    script.extract(collector, &consts, &bases);
+    script.chunks(collector, &consts, &bases);
+
+    // Nuxt 2静态部署时资源目录下的manifest.js列出全部路由
+    if let Some(dir) = &bases.nuxt_static {
+        collector.push_asset(&format!("{}/manifest.js", dir.trim_end_matches('/')), Extractor::Nuxt);
+    }
+
+    // 内联的source map直接解码处理，外部的按JS加入队列
+    match source_mapping_url(content) {
+        Some(url) if url.starts_with("data:") => {
+            let decoded = url.split_once(";base64,")
+                .and_then(|(_, data)| decode_base64(data))
+                .and_then(|bytes| String::from_utf8(bytes).ok());
+            if let Some(map) = decoded {
+                sourcemap::extract(collector, &map);
+            }
+        }
+        Some(url) => collector.push_asset(url, Extractor::Js),
+        None => {}
+    }
+}
+
+/// 文件末尾//# sourceMappingURL=注释中的地址，兼容旧式的//@和CSS式的/*# */
+pub(crate) fn source_mapping_url(content: &str) -> Option<&str> {
+    let start = content.rfind("sourceMappingURL=")?;
+    let before = content[..start].trim_end_matches([' ', '\t']);
+    if !["//#", "//@", "/*#", "/*@"].iter().any(|prefix| before.ends_with(prefix)) {
+        return None;
+    }
+    let value = &content[start + "sourceMappingURL=".len()..];
+    let end = value.find(|c: char| c.is_whitespace() || c == '*').unwrap_or(value.len());
+    let url = value[..end].trim_matches(['\'', '"']);
+    (!url.is_empty()).then_some(url)
+}
+
+/// 文件中推断出的基础地址
+#[derive(Debug, Default)]
+struct Bases {
+    /// 请求库的基础地址，如axios的baseURL、VUE_APP_BASE_API
+    api: Option<String>,
+    /// webpack的publicPath，异步加载的chunk相对于它
+    public_path: Option<String>,
+    /// Nuxt 2的staticAssetsBase
+    nuxt_static: Option<String>,
+    /// axios.create()创建的实例
+    instances: HashSet<String>,
+}
+
+/// 文件中被赋值为字符串的变量，多次赋不同值或无法求值的变量记为None
+#[derive(Debug, Default)]
+struct Consts(HashMap<String, Option<String>>);
+
+impl Consts {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name)?.as_deref()
+    }
+
+    fn set(&mut self, name: String, value: Option<String>) {
+        match (self.0.get(&name), &value) {
+            (None, _) => {
+                self.0.insert(name, value);
+            }
+            (Some(Some(old)), Some(new)) if old == new => {}
+            _ => {
+                self.0.insert(name, None);
+            }
+        }
+    }
+}
+
+/// 已切分的脚本，记录括号的配对位置
+struct Script<'t> {
+    tokens: &'t [Token],
+    /// 左括号对应的右括号
+    pairs: Vec<usize>,
+    /// 右括号对应的左括号
+    opens: Vec<usize>,
+}
+
+impl<'t> Script<'t> {
+    fn new(tokens: &'t [Token]) -> Self {
+        let (pairs, opens) = pair_brackets(tokens);
+        Script { tokens, pairs, opens }
+    }
+
+    /// 按出现顺序收集name = 字符串表达式形式的赋值，对象字面量收集一层属性
+    fn constants(&self) -> Consts {
+        let mut consts = Consts::default();
+
+        for (k, token) in self.tokens.iter().enumerate() {
+            if *token != Token::Punct("=") {
+                continue;
+            }
+            let Some(name) = self.assign_target(k) else {
+                continue;
+            };
+
+            if self.is_punct(k + 1, "{") {
+                for (key, range) in self.props(k + 1) {
+                    let value = self.eval(range, &consts);
+                    consts.set(format!("{}.{}", name, key), value);
+                }
+                continue;
+            }
+            let value = self.concat(k + 1, self.tokens.len(), &consts)
+                .filter(|(_, end)| self.is_expr_end(*end))
+                .map(|(value, _)| value);
+            consts.set(name, value);
+        }
+
+        consts
+    }
+
+    /// =左边的变量名或a.b.c形式的属性路径；压缩代码中单字母局部变量大量重名，不作处理
+    fn assign_target(&self, eq: usize) -> Option<String> {
+        let mut start = eq.checked_sub(1)?;
+        self.ident(start)?;
+        while start >= 2 && self.is_punct(start - 1, ".") && self.ident(start - 2).is_some() {
+            start -= 2;
+        }
+        if self.is_punct(start.wrapping_sub(1), ".") || self.is_punct(start.wrapping_sub(1), "?.") {
+            return None;
+        }
+
+        let name = (start..eq).step_by(2)
+            .filter_map(|i| self.ident(i))
+            .collect::<Vec<_>>()
+            .join(".");
+        (name.contains('.') || name.chars().count() > 1).then_some(name)
+    }
+
+    /// 查找axios.defaults.baseURL、{baseURL: ...}、VUE_APP_BASE_API、webpack publicPath
+    /// 和Nuxt 2的staticAssetsBase，各取第一个能求值的
+    fn bases(&self, consts: &Consts) -> Bases {
+        let mut bases = Bases::default();
+
+        for (k, token) in self.tokens.iter().enumerate() {
+            let found: Vec<(String, Range<usize>)> = match token {
+                Token::Punct("{") => self.props(k),
+                Token::Punct("=") => {
+                    // service = axios.create({...})
+                    if self.ident(k + 1) == Some("axios")
+                        && self.is_punct(k + 2, ".")
+                        && self.ident(k + 3) == Some("create")
+                        && self.is_punct(k + 4, "(")
+                    {
+                        if let Some(name) = self.ident(k.wrapping_sub(1)) {
+                            bases.instances.insert(name.to_string());
+                        }
+                    }
+                    match self.assign_target(k) {
+                        Some(name) => {
+                            let end = self.concat(k + 1, self.tokens.len(), consts).map_or(k + 1, |(_, end)| end);
+                            vec![(name, k + 1..end)]
+                        }
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+
+            for (name, range) in found {
+                let key = name.rsplit('.').next().unwrap_or(&name);
+                let slot = if key == "baseURL" || key.ends_with("BASE_API") {
+                    &mut bases.api
+                } else if is_public_path(&name) {
+                    &mut bases.public_path
+                } else if key == "staticAssetsBase" {
+                    &mut bases.nuxt_static
+                } else {
+                    continue;
+                };
+                if slot.is_none() {
+                    *slot = self.eval(range, consts).filter(|base| looks_like_base(base));
+                }
+            }
+        }
+
+        bases
+    }
+
+    fn extract(&self, collector: &mut Collector, consts: &Consts, bases: &Bases) {
+        // 已作为请求地址或路由处理过的字面量，不再按普通字面量输出
+        let mut consumed = vec![false; self.tokens.len()];
+
+        let mut nested = HashSet::new();
+        for i in 0..self.tokens.len() {
+            if self.is_punct(i, "{") && !nested.contains(&i) {
+                self.route(i, "", collector, consts, &mut nested, &mut consumed);
+            }
+        }
+
+        for (i, token) in self.tokens.iter().enumerate() {
+            match token {
+                Token::Ident(name) if self.is_punct(i + 1, "(") => {
+                    self.call(i, name, collector, consts, bases, &mut consumed);
+                }
+                Token::Punct("{") => self.object(i, collector, consts, bases, &mut consumed),
+                _ => {}
+            }
+        }
+
+        for (i, token) in self.tokens.iter().enumerate() {
+            if consumed[i] {
+                continue;
+            }
+
+            // 字符串拼接作为整体求值
+            if let Some((value, end)) = self.concat_at(i, consts) {
+                if looks_like_endpoint(&value) {
+                    collector.push_endpoint(&value, None, None);
+                    consumed[i..end].iter_mut().for_each(|c| *c = true);
+                    continue;
+                }
+            }
+
+            match token {
+                // 字符串中可能还有URL编码、HTML实体或Base64
+                Token::Str(value) => {
+                    let decoded = collector.decode(value);
+                    if looks_like_endpoint(&decoded) {
+                        collector.push_endpoint(&decoded, None, None);
+                    } else if decoded != *value {
+                        collector.scan_decoded(&decoded, Extractor::Js, None);
+                    }
+                }
+                Token::Template(template) => {
+                    if let Some(value) = self.render(template, consts).filter(|v| looks_like_endpoint(v)) {
+                        collector.push_endpoint(&value, None, None);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // 模板中的表达式可能包含其他请求
+        for token in self.tokens {
+            if let Token::Template(template) = token {
+                for expr in &template.exprs {
+                    Script::new(expr).extract(collector, consts, bases);
+                }
+            }
+        }
+    }
+
+    /// 还原webpack运行时中按chunk id拼接的地址，如
+    /// n.p + "js/" + ({1: "about"}[e] || e) + "." + {1: "3f2a"}[e] + ".js"，
+    /// 以映射表中的id生成全部chunk地址
+    fn chunks(&self, collector: &mut Collector, consts: &Consts, bases: &Bases) {
+        let mut seen = HashSet::new();
+
+        for (i, token) in self.tokens.iter().enumerate() {
+            if *token != Token::Punct("{") {
+                continue;
+            }
+            let Some(var) = self.subscript(i) else {
+                continue;
+            };
+            // ({...}[e] || e)作为一个操作数
+            let operand = match i.checked_sub(1) {
+                Some(open) if self.is_punct(open, "(") && self.pairs[open] == self.pairs[i] + 6 => open,
+                _ => i,
+            };
+            let start = self.chain_start(operand);
+            if !seen.insert(start) {
+                continue;
+            }
+            let Some(chain) = self.chunk_chain(start, var, consts) else {
+                continue;
+            };
+
+            // 公共路径未知时按站点根目录处理
+            let public_path = chain.public_path.as_deref()
+                .or(bases.public_path.as_deref())
+                .unwrap_or("/");
+            for path in chain.paths() {
+                if is_absolute(&path) || path.starts_with('/') {
+                    collector.push_endpoint(&path, None, None);
+                } else {
+                    collector.push_endpoint(&join_base(public_path, &path), None, Some(public_path));
+                }
+            }
+        }
+    }
+
+    /// {...}[id]形式的下标访问，返回下标变量名
+    fn subscript(&self, open: usize) -> Option<&str> {
+        let close = *self.pairs.get(open)?;
+        if !self.is_punct(close + 1, "[") || !self.is_punct(close + 3, "]") {
+            return None;
+        }
+        self.ident(close + 2)
+    }
+
+    /// 向前找到包含start的a + b + ...表达式的开头
+    fn chain_start(&self, mut start: usize) -> usize {
+        while start >= 2 && self.is_punct(start - 1, "+") {
+            match self.operand_start(start - 2) {
+                Some(operand) => start = operand,
+                None => break,
+            }
+        }
+        start
+    }
+
+    /// 以end结尾的操作数的开头，包括属性访问、调用和下标
+    fn operand_start(&self, end: usize) -> Option<usize> {
+        let mut i = end;
+        loop {
+            match self.tokens.get(i)? {
+                Token::Punct(")" | "]" | "}") => i = self.opens[i],
+                Token::Str(_) | Token::Num(_) | Token::Ident(_) | Token::Template(_) => {}
+                _ => return None,
+            }
+            if i >= self.tokens.len() {
+                return None;
+            }
+            let callee = i > 0 && (self.is_punct(i, "(") || self.is_punct(i, "["))
+                && matches!(self.tokens[i - 1], Token::Ident(_) | Token::Punct(")" | "]" | "}"));
+            if callee {
+                i -= 1;
+            } else if i >= 2 && self.is_punct(i - 1, ".") {
+                i -= 2;
+            } else {
+                return Some(i);
+            }
+        }
+    }
+
+    /// 解析chunk地址表达式，除chunk id和映射表外只允许字面量和开头的公共路径
+    fn chunk_chain(&self, start: usize, var: &str, consts: &Consts) -> Option<ChunkChain> {
+        let mut chain = ChunkChain::default();
+        let mut i = start;
+
+        loop {
+            let end = match self.tokens.get(i)? {
+                Token::Str(value) | Token::Num(value) => {
+                    chain.parts.push(ChunkPart::Lit(value.clone()));
+                    i + 1
+                }
+                Token::Punct("{") if self.subscript(i) == Some(var) => {
+                    chain.parts.push(ChunkPart::Map(self.string_map(i, consts)?, false));
+                    self.pairs[i] + 4
+                }
+                // ({...}[e] || e)
+                Token::Punct("(") => {
+                    let open = i + 1;
+                    let close = *self.pairs.get(open)?;
+                    let fallback = self.subscript(open) == Some(var)
+                        && self.is_punct(close + 4, "||")
+                        && self.ident(close + 5) == Some(var)
+                        && self.pairs[i] == close + 6;
+                    if !fallback {
+                        return None;
+                    }
+                    chain.parts.push(ChunkPart::Map(self.string_map(open, consts)?, true));
+                    close + 7
+                }
+                Token::Ident(word) if word == var && !self.is_punct(i + 1, ".") => {
+                    chain.parts.push(ChunkPart::Id);
+                    i + 1
+                }
+                Token::Ident(_) if i == start => {
+                    // 开头的属性路径为公共路径，如__webpack_require__.p
+                    let (value, end) = self.operand(i, self.tokens.len(), consts)?;
+                    let pure = (i..end).all(|j| self.ident(j).is_some() || self.is_punct(j, "."));
+                    if !pure {
+                        return None;
+                    }
+                    chain.public_path = value;
+                    end
+                }
+                _ => return None,
+            };
+
+            if self.is_punct(end, "+") {
+                i = end + 1;
+            } else {
+                break;
+            }
+        }
+
+        chain.parts.iter().any(|part| matches!(part, ChunkPart::Map(..))).then_some(chain)
+    }
+
+    /// 值均为字符串的对象字面量
+    fn string_map(&self, open: usize, consts: &Consts) -> Option<HashMap<String, String>> {
+        self.props(open).into_iter()
+            .map(|(key, range)| Some((key, self.eval(range, consts)?)))
+            .collect()
+    }
+
+    /// 识别{path: ..., component: ...}形式的路由定义，children中的子路由拼接父路由路径；
+    /// 不是路由定义时返回false
+    fn route(
+        &self,
+        open: usize,
+        parent: &str,
+        collector: &mut Collector,
+        consts: &Consts,
+        nested: &mut HashSet<usize>,
+        consumed: &mut [bool],
+    ) -> bool {
+        let props = self.props(open);
+        if !props.iter().any(|(key, _)| ROUTE_KEYS.contains(&key.as_str())) {
+            return false;
+        }
+        let Some((_, path_range)) = props.iter().find(|(key, _)| key == "path") else {
+            return false;
+        };
+        let Some(path) = self.eval(path_range.clone(), consts) else {
+            return false;
+        };
+        consumed[path_range.clone()].iter_mut().for_each(|c| *c = true);
+
+        let full = join_route(parent, &path);
+        // 通配的兜底路由没有对应页面
+        if !matches!(path.as_str(), "*" | "**") && !path.contains("(.*)") {
+            collector.push_route(full.clone());
+        }
+        for (key, range) in &props {
+            match key.as_str() {
+                "redirect" | "redirectTo" => {
+                    if let Some(target) = self.eval(range.clone(), consts) {
+                        collector.push_route(join_route(parent, &target));
+                        consumed[range.clone()].iter_mut().for_each(|c| *c = true);
+                    }
+                }
+                // 子路由可能包在数组、调用(如React的jsx())中
+                "children" => {
+                    let mut j = range.start;
+                    while j < range.end {
+                        if self.is_punct(j, "{") && self.route(j, &full, collector, consts, nested, consumed) {
+                            nested.insert(j);
+                            j = self.pairs[j];
+                        }
+                        j += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        true
+    }
+
+    /// 识别fetch、axios、$.ajax、XMLHttpRequest.open、new URL、import()等调用
+    fn call(&self, i: usize, name: &str, collector: &mut Collector, consts: &Consts, bases: &Bases, consumed: &mut [bool]) {
+        let is_member = self.is_punct(i.wrapping_sub(1), ".") || self.is_punct(i.wrapping_sub(1), "?.");
+        let is_new = matches!(self.tokens.get(i.wrapping_sub(1)), Some(Token::Ident(word)) if word == "new");
+        let args = self.args(i + 1);
+        let Some(first) = args.first().cloned() else {
+            return;
+        };
+
+        // 只有axios风格的调用使用baseURL，fetch、XMLHttpRequest等不受影响
+        let (url_arg, url, method, api) = match name {
+            "fetch" | "axios" | "ajax" | "Request" if name != "Request" || is_new => {
+                let method = args.get(1)
+                    .and_then(|options| self.option_method(options, consts))
+                    .unwrap_or_else(|| "GET".to_string());
+                (first.clone(), self.eval(first, consts), Some(method), name == "axios")
+            }
+            "URL" if is_new => {
+                // 有基础地址时按其解析
+                let url = self.eval(first.clone(), consts).map(|url| {
+                    // 解析时无法确定的表达式的标记被编码为%00
+                    let unknown = url.contains(UNKNOWN);
+                    args.get(1)
+                        .and_then(|base| self.eval(base.clone(), consts))
+                        .and_then(|base| Url::parse(&base).ok()?.join(&url).ok())
+                        .map_or(url, |joined| {
+                            if unknown { joined.as_str().replace("%00", UNKNOWN) } else { joined.to_string() }
+                        })
+                });
+                (first, url, None, false)
+            }
+            // 动态import()加载的模块
+            "import" if !is_member => (first.clone(), self.eval(first, consts), None, false),
+            "open" if is_member && args.len() >= 2 => {
+                let Some(method) = self.eval(first, consts).and_then(|m| http_method(&m)) else {
+                    return;
+                };
+                (args[1].clone(), self.eval(args[1].clone(), consts), Some(method), false)
+            }
+            // axios.get、service.post、$.get等，地址需包含/以排除Map.get等普通调用
+            verb if is_member && http_method(verb).is_some() => {
+                let url = self.eval(first.clone(), consts).filter(|url| url.contains('/'));
+                (first, url, http_method(verb), true)
+            }
+            _ => return,
+        };
+
+        if let Some(url) = url.filter(|url| looks_like_request_url(url)) {
+            push_with_base(collector, bases, &url, method.as_deref(), api);
+            consumed[url_arg].iter_mut().for_each(|c| *c = true);
+        }
+    }
+
+    /// 识别{url: ..., method: ...}形式的请求配置，如axios({...})、$.ajax({...})
+    /// 只有axios及其实例调用的配置拼接baseURL
+    fn object(&self, open: usize, collector: &mut Collector, consts: &Consts, bases: &Bases, consumed: &mut [bool]) {
+        let props = self.props(open);
+        let Some((_, url_range)) = props.iter().find(|(key, _)| key == "url") else {
+            return;
+        };
+        let Some(url) = self.eval(url_range.clone(), consts).filter(|url| looks_like_request_url(url)) else {
+            return;
+        };
+        let method = props.iter()
+            .filter(|(key, _)| key == "method" || key == "type")
+            .find_map(|(_, range)| self.eval(range.clone(), consts).and_then(|m| http_method(&m)));
+        push_with_base(collector, bases, &url, method.as_deref(), self.is_axios_arg(open, bases));
+        consumed[url_range.clone()].iter_mut().for_each(|c| *c = true);
+    }
+
+    /// 对象是否为axios(...)、axios.request(...)或axios实例调用的第一个参数
+    fn is_axios_arg(&self, open: usize, bases: &Bases) -> bool {
+        if !self.is_punct(open.wrapping_sub(1), "(") {
+            return false;
+        }
+        let mut callee = open.wrapping_sub(2);
+        if self.ident(callee) == Some("request") && self.is_punct(callee.wrapping_sub(1), ".") {
+            callee = callee.wrapping_sub(2);
+        } else if self.is_punct(callee.wrapping_sub(1), ".") || self.is_punct(callee.wrapping_sub(1), "?.") {
+            return false;
+        }
+        self.ident(callee).is_some_and(|name| name == "axios" || bases.instances.contains(name))
+    }
+
+    /// 从请求选项对象中读取method
+    fn option_method(&self, options: &Range<usize>, consts: &Consts) -> Option<String> {
+        if !self.is_punct(options.start, "{") {
+            return None;
+        }
+        self.props(options.start).into_iter()
+            .filter(|(key, _)| key == "method" || key == "type")
+            .find_map(|(_, range)| self.eval(range, consts).and_then(|m| http_method(&m)))
+    }
+
+    /// 计算表达式的字符串值，表达式需完整覆盖range
+    fn eval(&self, range: Range<usize>, consts: &Consts) -> Option<String> {
+        let (value, end) = self.concat(range.start, range.end, consts)?;
+        (end == range.end).then_some(value)
+    }
+
+    /// 从i开始、不是其他拼接一部分的a + b + ...表达式
+    fn concat_at(&self, i: usize, consts: &Consts) -> Option<(String, usize)> {
+        if ["+", ".", "?."].iter().any(|p| self.is_punct(i.wrapping_sub(1), p)) {
+            return None;
+        }
+        // 调用的括号不是分组表达式
+        let prev_is_callee = self.ident(i.wrapping_sub(1)).is_some()
+            || self.is_punct(i.wrapping_sub(1), ")")
+            || self.is_punct(i.wrapping_sub(1), "]");
+        if self.is_punct(i, "(") && prev_is_callee {
+            return None;
+        }
+        let (_, end) = self.operand(i, self.tokens.len(), consts)?;
+        if !self.is_punct(end, "+") {
+            return None;
+        }
+        self.concat(i, self.tokens.len(), consts)
+    }
+
+    /// 从start开始解析以+连接的操作数，返回拼接结果和表达式结束位置；
+    /// 未知操作数用占位符表示，开头的未知操作数通常为基础地址，直接省略
+    fn concat(&self, start: usize, limit: usize, consts: &Consts) -> Option<(String, usize)> {
+        let mut out = String::new();
+        let mut known = false;
+        let mut i = start;
+
+        loop {
+            let (value, end) = self.operand(i, limit, consts)?;
+            match value {
+                Some(value) => {
+                    out.push_str(&value);
+                    known = true;
+                }
+                None if out.is_empty() => {}
+                None => out.push_str(UNKNOWN),
+            }
+            i = end;
+            if i < limit && self.is_punct(i, "+") {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+
+        (known && !out.is_empty()).then_some((out, i))
+    }
+
+    /// 解析一个操作数，返回其值(未知时为None)和结束位置；不是操作数时返回None
+    fn operand(&self, i: usize, limit: usize, consts: &Consts) -> Option<(Option<String>, usize)> {
+        if i >= limit {
+            return None;
+        }
+        match &self.tokens[i] {
+            Token::Str(value) => Some((Some(value.clone()), i + 1)),
+            Token::Num(value) => Some((Some(value.clone()), i + 1)),
+            Token::Template(template) => Some((self.render(template, consts), i + 1)),
+            // 未闭合的括号不作为操作数
+            Token::Punct("(") if self.pairs[i] < limit => {
+                let close = self.pairs[i];
+                Some((self.eval(i + 1..close, consts), close + 1))
+            }
+            Token::Ident(word) if !RESERVED.contains(&word.as_str()) => {
+                // a.b.c形式的属性路径可以查找常量，含调用或下标时值未知
+                let mut path = word.clone();
+                let mut pure = true;
+                let mut j = i + 1;
+                while j < limit {
+                    if (self.is_punct(j, ".") || self.is_punct(j, "?.")) && self.ident(j + 1).is_some() {
+                        path.push('.');
+                        path.push_str(self.ident(j + 1).unwrap_or_default());
+                        j += 2;
+                    } else if self.is_punct(j, "(") || self.is_punct(j, "[") {
+                        pure = false;
+                        j = self.pairs[j] + 1;
+                    } else {
+                        break;
+                    }
+                }
+                let value = if pure { consts.get(&path).map(String::from) } else { None };
+                Some((value, j.min(limit)))
+            }
+            _ => None,
+        }
+    }
+
+    /// 拼出模板字面量，无法确定的表达式用占位符表示，开头的未知表达式直接省略
+    fn render(&self, template: &Template, consts: &Consts) -> Option<String> {
+        let mut out = String::new();
+        for (i, quasi) in template.quasis.iter().enumerate() {
+            out.push_str(quasi);
+            if let Some(expr) = template.exprs.get(i) {
+                match Script::new(expr).eval(0..expr.len(), consts) {
+                    Some(value) => out.push_str(&value),
+                    None if out.is_empty() => {}
+                    None => out.push_str(UNKNOWN),
+                }
+            }
+        }
+        (!out.is_empty()).then_some(out)
+    }
+
+    /// 表达式是否在此结束，后面为分隔符或省略分号后的下一条语句
+    fn is_expr_end(&self, i: usize) -> bool {
+        match self.tokens.get(i) {
+            None | Some(Token::Ident(_)) => true,
+            Some(Token::Punct(p)) => matches!(*p, ";" | "," | ")" | "]" | "}"),
+            _ => false,
+        }
+    }
+
+    fn ident(&self, i: usize) -> Option<&str> {
+        match self.tokens.get(i) {
+            Some(Token::Ident(word)) if !RESERVED.contains(&word.as_str()) => Some(word),
+            _ => None,
+        }
+    }
+
+    /// 调用的参数列表，open为左括号位置
+    fn args(&self, open: usize) -> Vec<Range<usize>> {
+        split_items(self.tokens, &self.pairs, open)
+    }
+
+    /// 对象字面量的属性，open为左花括号位置
+    fn props(&self, open: usize) -> Vec<(String, Range<usize>)> {
+        self.args(open).into_iter()
+            .filter_map(|range| {
+                let key = match self.tokens.get(range.start)? {
+                    Token::Ident(name) | Token::Str(name) | Token::Num(name) => name.clone(),
+                    _ => return None,
+                };
+                if self.is_punct(range.start + 1, ":") {
+                    Some((key, range.start + 2..range.end))
+                } else if range.len() == 1 {
+                    // 简写属性{url}
+                    Some((key, range))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn is_punct(&self, i: usize, punct: &str) -> bool {
+        matches!(self.tokens.get(i), Some(Token::Punct(p)) if *p == punct)
+    }
+}
+
+/// webpack运行时中的chunk地址表达式
+#[derive(Debug, Default)]
+struct ChunkChain {
+    public_path: Option<String>,
+    parts: Vec<ChunkPart>,
+}
+
+#[derive(Debug)]
+enum ChunkPart {
+    Lit(String),
+    /// chunk id本身
+    Id,
+    /// 按chunk id查找的映射表，为true时找不到则使用id本身
+    Map(HashMap<String, String>, bool),
+}
+
+impl ChunkChain {
+    /// 映射表中的每个chunk id对应的地址，只保留JS；
+    /// id取自必须命中的映射表(通常为hash表)，没有时取名称表
+    fn paths(&self) -> Vec<String> {
+        let required = self.parts.iter().any(|part| matches!(part, ChunkPart::Map(_, false)));
+        let mut ids: Vec<&String> = self.parts.iter()
+            .filter_map(|part| match part {
+                ChunkPart::Map(map, fallback) if *fallback != required => Some(map.keys()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        ids.sort();
+        ids.dedup();
+
+        ids.into_iter()
+            .filter_map(|id| {
+                self.parts.iter()
+                    .map(|part| match part {
+                        ChunkPart::Lit(value) => Some(value.as_str()),
+                        ChunkPart::Id => Some(id.as_str()),
+                        ChunkPart::Map(map, true) => Some(map.get(id).map_or(id.as_str(), String::as_str)),
+                        ChunkPart::Map(map, false) => map.get(id).map(String::as_str),
+                    })
+                    .collect::<Option<String>>()
+            })
+            .filter(|path| is_js_path(path))
+            .collect()
+    }
+}
+
+/// 拼接父路由和子路由路径，以/开头的子路由为绝对路径
+fn join_route(parent: &str, path: &str) -> String {
+    if path.starts_with('/') {
+        return path.to_string();
+    }
+    let parent = parent.trim_end_matches('/');
+    if path.is_empty() {
+        return if parent.is_empty() { "/".to_string() } else { parent.to_string() };
+    }
+    format!("{}/{}", parent, path)
+}
+
+/// 拼接推断出的基础地址后添加: 相对路径的JS按publicPath加载，
+/// api为true时相对路径的接口按axios的规则拼接baseURL，完整URL不受影响
+fn push_with_base(collector: &mut Collector, bases: &Bases, url: &str, method: Option<&str>, api: bool) {
+    let base = if is_js_path(url) {
+        bases.public_path.as_deref().filter(|_| !url.starts_with('/') && !is_absolute(url))
+    } else {
+        bases.api.as_deref().filter(|_| api && !is_absolute(url))
+    };
+
+    match base {
+        Some(base) => collector.push_endpoint(&join_base(base, url), method, Some(base)),
+        None => collector.push_endpoint(url, method, None),
+    }
+}
+
+/// 拼接基础地址和相对路径，中间只保留一个/
+fn join_base(base: &str, url: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), url.trim_start_matches("./").trim_start_matches('/'))
+}
+
+/// 带协议或协议相对的地址
+fn is_absolute(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://") || url.starts_with("//")
+}
+
+/// __webpack_require__.p、压缩后的n.p、__webpack_public_path__和publicPath配置
+fn is_public_path(name: &str) -> bool {
+    let segments: Vec<&str> = name.split('.').collect();
+    matches!(segments.as_slice(), [_, "p"] | [.., "publicPath"] | ["__webpack_public_path__"])
+}
+
+/// 基础地址需为完整URL或路径
+fn looks_like_base(value: &str) -> bool {
+    !value.contains(char::is_whitespace)
+        && !value.contains(UNKNOWN)
+        && (is_absolute(value) || value.starts_with('/'))
+}
+
+fn http_method(value: &str) -> Option<String> {
+    let method = value.to_ascii_uppercase();
+    HTTP_METHODS.contains(&method.as_str()).then_some(method)
+}
+
+/// 请求调用中的地址，允许相对路径
+fn looks_like_request_url(value: &str) -> bool {
+    let value = value.trim();
+    !value.is_empty()
+        && value != UNKNOWN
+        && !value.contains(char::is_whitespace)
+        && !value.starts_with('#')
+}
+
+/// 普通字面量是否像接口地址: 完整URL或以单个/开头的路径，排除MIME类型、日期等
+fn looks_like_endpoint(value: &str) -> bool {
+    let value = &*show_unknown(value);
+    if value.len() < 2 || value.len() > 2048 || value.contains(char::is_whitespace) {
+        return false;
+    }
+
+    let lower = value.to_ascii_lowercase();
+    if let Some(rest) = lower.strip_prefix("http://").or_else(|| lower.strip_prefix("https://")) {
+        return rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '[');
+    }
+    if let Some(host) = value.strip_prefix("//") {
+        // 协议相对地址需要带域名
+        let host = host.split(['/', '?', '#']).next().unwrap_or("");
+        return host.contains('.') && host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'));
+    }
+
+    value.starts_with('/')
+        && value[1..].starts_with(|c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '~' | '$' | '@' | ':'))
+        // 日期、分数等只有数字和/的值
+        && !value.chars().all(|c| c.is_ascii_digit() || c == '/')
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || "-._~:/?#[]@!$&'()*+,;=%{}".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::extract::{extract, ExtractOptions, Extracted};
+    use crate::rules::RuleEngine;
+
+    fn run(content: &str) -> Extracted {
+        let rules = RuleEngine::compile(&Config::default()).unwrap();
+        let options = ExtractOptions { mode: 2, ..Default::default() };
+        extract(&rules, &options, "application/javascript", "http://example.com/static/app.js", content)
+    }
+
+    fn urls(extracted: &Extracted) -> Vec<&str> {
+        extracted.urls.iter().map(|link| link.url.as_str()).collect()
+    }
+
+    fn methods(extracted: &Extracted) -> Vec<(&str, Option<&str>)> {
+        extracted.urls.iter().map(|link| (link.url.as_str(), link.method.as_deref())).collect()
+    }
+
+    #[test]
+    fn detects_request_methods() {
+        let extracted = run(r#"
+            fetch("/api/a", {method: "post"});
+            axios.put("/api/b");
+            $.ajax({url: "/api/c", type: "delete"});
+            xhr.open("PATCH", "/api/d");
+            new Request("/api/e");
+        "#);
+        assert_eq!(methods(&extracted), [
+            ("http://example.com/api/a", Some("POST")),
+            ("http://example.com/api/b", Some("PUT")),
+            ("http://example.com/api/c", Some("DELETE")),
+            ("http://example.com/api/d", Some("PATCH")),
+            ("http://example.com/api/e", Some("GET")),
+        ]);
+    }
+
+    #[test]
+    fn propagates_constants_in_concatenation() {
+        let extracted = run(r#"
+            const API = "/api/v2", cfg = {prefix: "/admin"};
+            // 多次赋不同值的变量值未知
+            let version = "1"; version = "2";
+            fetch(API + "/users/" + id + "/roles");
+            fetch(cfg.prefix + `/logs?v=${version}`);
+            x = API + "/plain";
+        "#);
+        assert_eq!(urls(&extracted), [
+            "http://example.com/api/v2/users/EXPR/roles",
+            "http://example.com/admin/logs?v=EXPR",
+            "http://example.com/api/v2",
+            "http://example.com/admin",
+            "http://example.com/api/v2/plain",
+        ]);
+    }
+
+    #[test]
+    fn reconstructs_chunk_maps() {
+        let extracted = run(r#"
+            n.p = "/static/";
+            n.u = function(e) {
+                return "js/" + ({12: "about", 34: "user"}[e] || e) + "." + {12: "a1b2", 34: "c3d4", 56: "e5f6"}[e] + ".js";
+            };
+        "#);
+        let js_urls: Vec<&str> = extracted.js_urls.iter().map(|link| link.url.as_str()).collect();
+        assert_eq!(js_urls, [
+            "http://example.com/static/js/about.a1b2.js",
+            "http://example.com/static/js/user.c3d4.js",
+            "http://example.com/static/js/56.e5f6.js",
+        ]);
+    }
+
+    #[test]
+    fn tolerates_unbalanced_brackets() {
+        assert!(urls(&run(r#"var q = "/api/a" + ("b""#)).contains(&"http://example.com/api/a"));
+        assert!(urls(&run(r#"f("/api/a" + (x"#)).contains(&"http://example.com/api/a"));
+        run(r#"o.u=function(e){return "static/js/"+{1:"a"}[e]+("#);
+        run("a = ([{");
+    }
+
+    #[test]
+    fn marks_unresolved_expressions() {
+        let extracted = run(r#"fetch("/order/" + id + "/detail"); fetch(`/user/${id}`); x = "/api/EXPRESS/list";"#);
+        let unresolved: Vec<(&str, bool)> = extracted.urls.iter().map(|link| (link.url.as_str(), link.unresolved)).collect();
+        assert!(unresolved.contains(&("http://example.com/order/EXPR/detail", true)));
+        assert!(unresolved.contains(&("http://example.com/user/EXPR", true)));
+        assert!(unresolved.contains(&("http://example.com/api/EXPRESS/list", false)));
+    }
+
+    #[test]
+    fn decodes_strings_once() {
+        let extracted = run(r#"x = "path=%252fapi%252fuser;";"#);
+        assert!(urls(&extracted).iter().all(|url| !url.ends_with("/api/user")));
+        let extracted = run(r#"x = "path=%2fapi%2fuser;";"#);
+        assert!(urls(&extracted).contains(&"http://example.com/api/user"));
+    }
+
+    #[test]
+    fn limits_template_nesting() {
+        let nested = format!("{}\"/api/deep\"{}", "`${".repeat(5000), "}`".repeat(5000));
+        run(&format!("fetch(\"/api/user\"); x = {};", nested));
+    }
+}
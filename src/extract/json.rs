@@ -0,0 +1,20 @@
+use serde_json::Value;
+
+use super::{Collector, Extractor};
+
+/// 解析JSON并匹配其中的字符串值，解析失败时按文本处理
+pub(crate) fn extract(collector: &mut Collector, content: &str) {
+    match serde_json::from_str::<Value>(content.trim_start_matches('\u{feff}')) {
+        Ok(value) => walk(collector, &value),
+        Err(_) => collector.scan(content, Extractor::Json),
+    }
+}
+
+fn walk(collector: &mut Collector, value: &Value) {
+    match value {
+        Value::String(s) => collector.scan(s, Extractor::Json),
+        Value::Array(items) => items.iter().for_each(|item| walk(collector, item)),
+        Value::Object(map) => map.values().for_each(|item| walk(collector, item)),
+        _ => {}
+    }
+}
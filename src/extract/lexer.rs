@@ -0,0 +1,369 @@
+use std::ops::Range;
+
+/// JS词法单元，只区分提取接口需要的类型
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    /// 标识符和关键字
+    Ident(String),
+    /// 运算符和标点
+    Punct(&'static str),
+    /// 字符串字面量，已处理转义
+    Str(String),
+    /// 模板字面量
+    Template(Template),
+    /// 数字字面量
+    Num(String),
+    /// 正则字面量
+    Other,
+}
+
+/// 模板字面量，quasis比exprs多一个
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Template {
+    pub quasis: Vec<String>,
+    pub exprs: Vec<Vec<Token>>,
+}
+
+/// 按最长匹配排列的运算符
+const PUNCTUATORS: &[&str] = &[
+    ">>>=", "...", "===", "!==", "**=", "<<=", ">>=", ">>>", "&&=", "||=", "??=",
+    "=>", "==", "!=", "<=", ">=", "&&", "||", "??", "?.", "++", "--", "+=", "-=", "*=", "/=",
+    "%=", "&=", "|=", "^=", "**", "<<", ">>",
+    "{", "}", "(", ")", "[", "]", ";", ",", "<", ">", "+", "-", "*", "/", "%", "&", "|", "^",
+    "!", "~", "?", ":", "=", ".", "@", "#",
+];
+
+/// 其后出现的/为正则开头而非除号的关键字
+const REGEX_KEYWORDS: &[&str] = &[
+    "return", "typeof", "case", "do", "else", "in", "instanceof", "new", "delete", "void",
+    "throw", "yield", "await", "of",
+];
+
+/// 模板表达式的最大嵌套层数，更深的${...}按普通文本处理
+const MAX_TEMPLATE_DEPTH: usize = 32;
+
+pub(crate) fn tokenize(source: &str) -> Vec<Token> {
+    let mut lexer = Lexer { src: source, pos: 0, nesting: 0 };
+    lexer.tokens(false)
+}
+
+/// 各括号的配对位置: 左括号对应的右括号和右括号对应的左括号，未配对的记为tokens.len()
+pub(crate) fn pair_brackets(tokens: &[Token]) -> (Vec<usize>, Vec<usize>) {
+    let mut pairs = vec![tokens.len(); tokens.len()];
+    let mut opens = vec![tokens.len(); tokens.len()];
+    let mut stack = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct("(" | "[" | "{") => stack.push(i),
+            Token::Punct(")" | "]" | "}") => {
+                if let Some(open) = stack.pop() {
+                    pairs[open] = i;
+                    opens[i] = open;
+                }
+            }
+            _ => {}
+        }
+    }
+    (pairs, opens)
+}
+
+/// 括号内以逗号分隔的各项，open为左括号位置，pairs为pair_brackets的结果
+pub(crate) fn split_items(tokens: &[Token], pairs: &[usize], open: usize) -> Vec<Range<usize>> {
+    let close = pairs.get(open).copied().unwrap_or(tokens.len());
+    let mut items = Vec::new();
+    let mut start = open + 1;
+    let mut i = open + 1;
+    while i < close {
+        match tokens[i] {
+            Token::Punct("(" | "[" | "{") => i = pairs[i],
+            Token::Punct(",") => {
+                items.push(start..i);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    if start < close {
+        items.push(start..close);
+    }
+    items
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+    /// 当前所在模板表达式的层数
+    nesting: usize,
+}
+
+impl Lexer<'_> {
+    /// 读取词法单元直到结尾，in_template为true时读到模板表达式的}为止
+    fn tokens(&mut self, in_template: bool) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut depth = 0usize;
+
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += c.len_utf8();
+                continue;
+            }
+            let rest = &self.src[self.pos..];
+
+            // 注释
+            if rest.starts_with("//") || rest.starts_with("<!--") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+                continue;
+            }
+            if let Some(body) = rest.strip_prefix("/*") {
+                self.pos += 2 + body.find("*/").map_or(body.len(), |end| end + 2);
+                continue;
+            }
+
+            match c {
+                '"' | '\'' => {
+                    self.pos += 1;
+                    tokens.push(Token::Str(self.string(c)));
+                }
+                '`' => {
+                    self.pos += 1;
+                    tokens.push(Token::Template(self.template()));
+                }
+                '0'..='9' => tokens.push(Token::Num(self.number())),
+                '.' if rest[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                    tokens.push(Token::Num(self.number()));
+                }
+                '/' if regex_allowed(tokens.last()) && self.regex() => tokens.push(Token::Other),
+                c if is_ident_start(c) => tokens.push(Token::Ident(self.ident())),
+                _ => {
+                    let Some(punct) = PUNCTUATORS.iter().find(|p| rest.starts_with(**p)) else {
+                        self.pos += c.len_utf8();
+                        continue;
+                    };
+                    match *punct {
+                        "{" => depth += 1,
+                        "}" if in_template && depth == 0 => {
+                            self.pos += 1;
+                            return tokens;
+                        }
+                        "}" => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    self.pos += punct.len();
+                    tokens.push(Token::Punct(punct));
+                }
+            }
+        }
+
+        tokens
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// 读取引号之后的字符串内容，直到对应的结束引号
+    fn string(&mut self, quote: char) -> String {
+        let mut value = String::new();
+        while let Some(c) = self.next_char() {
+            match c {
+                c if c == quote => break,
+                // 未闭合的字符串到行尾结束
+                '\n' => break,
+                '\\' => self.escape(&mut value),
+                c => value.push(c),
+            }
+        }
+        value
+    }
+
+    /// 读取反引号之后的模板内容
+    fn template(&mut self) -> Template {
+        let mut quasis = Vec::new();
+        let mut exprs = Vec::new();
+        let mut current = String::new();
+
+        while let Some(c) = self.next_char() {
+            match c {
+                '`' => break,
+                '\\' => self.escape(&mut current),
+                '$' if self.peek() == Some('{') && self.nesting < MAX_TEMPLATE_DEPTH => {
+                    self.pos += 1;
+                    quasis.push(std::mem::take(&mut current));
+                    self.nesting += 1;
+                    exprs.push(self.tokens(true));
+                    self.nesting -= 1;
+                }
+                c => current.push(c),
+            }
+        }
+        quasis.push(current);
+        Template { quasis, exprs }
+    }
+
+    /// 处理\之后的转义序列
+    fn escape(&mut self, out: &mut String) {
+        let Some(c) = self.next_char() else {
+            return;
+        };
+        match c {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            'v' => out.push('\u{b}'),
+            '0' => out.push('\0'),
+            // 行尾的\为续行
+            '\r' | '\n' => {}
+            'x' => match self.hex(2) {
+                Some(decoded) => out.push(decoded),
+                None => out.push('x'),
+            },
+            'u' => {
+                let decoded = if self.peek() == Some('{') {
+                    let rest = &self.src[self.pos + 1..];
+                    rest.find('}').and_then(|end| {
+                        let code = u32::from_str_radix(&rest[..end], 16).ok()?;
+                        self.pos += end + 2;
+                        char::from_u32(code)
+                    })
+                } else {
+                    self.hex(4)
+                };
+                out.push(decoded.unwrap_or('u'));
+            }
+            c => out.push(c),
+        }
+    }
+
+    /// 读取定长的十六进制字符码
+    fn hex(&mut self, len: usize) -> Option<char> {
+        let digits = self.src.get(self.pos..self.pos + len)?;
+        let code = u32::from_str_radix(digits, 16).ok()?;
+        let c = char::from_u32(code)?;
+        self.pos += len;
+        Some(c)
+    }
+
+    fn number(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        self.src[start..self.pos].to_string()
+    }
+
+    fn ident(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if is_ident_start(c) || c.is_ascii_digit() {
+                self.pos += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+        self.src[start..self.pos].to_string()
+    }
+
+    /// 读取正则字面量，同一行内找不到结尾时按除号处理
+    fn regex(&mut self) -> bool {
+        let mut in_class = false;
+        let mut chars = self.src[self.pos + 1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\n' => return false,
+                '\\' => {
+                    chars.next();
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => {
+                    self.pos += i + 2;
+                    // 修饰符
+                    while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                        self.pos += 1;
+                    }
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$' || (!c.is_ascii() && !c.is_whitespace())
+}
+
+/// 根据前一个词法单元判断/是否为正则开头
+fn regex_allowed(prev: Option<&Token>) -> bool {
+    match prev {
+        None => true,
+        Some(Token::Punct(p)) => !matches!(*p, ")" | "]" | "}" | "++" | "--"),
+        Some(Token::Ident(word)) => REGEX_KEYWORDS.contains(&word.as_str()),
+        Some(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pair_brackets, split_items, tokenize, Template, Token};
+
+    fn strs(source: &str) -> Vec<String> {
+        tokenize(source).into_iter()
+            .filter_map(|token| match token {
+                Token::Str(value) => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tokenizes_strings_and_comments() {
+        assert_eq!(strs(r#"a = "/api\/user"; // "/skip"
+            b = '\x2fv1/list'; /* "/skip" */"#), ["/api/user", "/v1/list"]);
+        // 未闭合的字符串到行尾结束
+        assert_eq!(strs("a = \"/open\nb = '/next'"), ["/open", "/next"]);
+    }
+
+    #[test]
+    fn distinguishes_regex_from_division() {
+        let tokens = tokenize(r#"x = a / b / "/c"; y = /"[a-z]"/g.test(s); return /"/.source"#);
+        assert_eq!(tokens.iter().filter(|token| **token == Token::Other).count(), 2);
+        assert_eq!(strs(r#"x = a / b / "/c""#), ["/c"]);
+    }
+
+    #[test]
+    fn tokenizes_templates() {
+        let tokens = tokenize("`/user/${id}/list?q=${`${a}`}`");
+        let [Token::Template(Template { quasis, exprs })] = tokens.as_slice() else {
+            panic!("unexpected tokens: {:?}", tokens);
+        };
+        assert_eq!(quasis, &["/user/", "/list?q=", ""]);
+        assert_eq!(exprs[0], [Token::Ident("id".to_string())]);
+        assert!(matches!(exprs[1].as_slice(), [Token::Template(_)]));
+    }
+
+    #[test]
+    fn pairs_brackets_and_splits_items() {
+        let tokens = tokenize("f(a, [b, c], {d: e}, (g");
+        let (pairs, opens) = pair_brackets(&tokens);
+        assert_eq!((pairs[4], opens[8]), (8, 4));
+        assert_eq!(pairs[10], 14);
+        // 未闭合的括号配对到结尾
+        assert_eq!((pairs[1], pairs[16]), (tokens.len(), tokens.len()));
+        assert_eq!(split_items(&tokens, &pairs, 1), [2..3, 4..9, 10..15, 16..18]);
+    }
+}
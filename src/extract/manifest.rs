@@ -0,0 +1,171 @@
+use serde_json::Value;
+
+use super::lexer::{tokenize, Token};
+use super::{is_js_path, json, Collector, Extractor};
+
+/// 按URL识别框架的构建清单，出错页面等HTML内容不处理
+pub(crate) fn detect(url: &str, content: &str) -> Option<Extractor> {
+    let path = url.split(['?', '#']).next().unwrap_or("").to_ascii_lowercase();
+    let head = content.trim_start_matches('\u{feff}').trim_start();
+    if head.starts_with('<') {
+        return None;
+    }
+
+    if path.ends_with("/_buildmanifest.js") || path.ends_with("/_ssgmanifest.js") {
+        Some(Extractor::Next)
+    } else if path.contains("/_nuxt/") && (path.ends_with("/manifest.js") || path.contains("/builds/")) {
+        Some(Extractor::Nuxt)
+    } else if path.ends_with("/manifest.json") && head.starts_with('{') && content.contains("\"file\"") {
+        Some(Extractor::Vite)
+    } else {
+        None
+    }
+}
+
+/// 根据页面引用的脚本识别框架，将其构建清单加入队列；module为<script type="module">或modulepreload
+pub(crate) fn discover(collector: &mut Collector, src: &str, module: bool) {
+    let Some(url) = collector.resolve(src) else {
+        return;
+    };
+
+    if url.contains("/_next/static/") {
+        // 页面通常只引用_buildManifest.js，_ssgManifest.js在同一目录
+        if let Some(dir) = url.split(['?', '#']).next().unwrap_or("").strip_suffix("/_buildManifest.js") {
+            collector.push_asset(&format!("{}/_ssgManifest.js", dir), Extractor::Next);
+        }
+    } else if let Some(i) = url.find("/_nuxt/") {
+        // Nuxt 3的当前构建信息，其中的id指向路由清单
+        collector.push_asset(&format!("{}builds/latest.json", &url[..i + 7]), Extractor::Nuxt);
+    } else if let Some(i) = url.find("/assets/").filter(|_| module) {
+        // Vite 5起清单位于.vite目录下，之前位于输出目录根部
+        let root = &url[..i + 1];
+        collector.push_asset(&format!("{}.vite/manifest.json", root), Extractor::Vite);
+        collector.push_asset(&format!("{}manifest.json", root), Extractor::Vite);
+    }
+}
+
+/// Next.js的__NEXT_DATA__中记录了buildId，据此找到构建清单
+pub(crate) fn next_data(collector: &mut Collector, content: &str) {
+    let Ok(data) = serde_json::from_str::<Value>(content.trim()) else {
+        return;
+    };
+    let Some(build_id) = data.get("buildId").and_then(Value::as_str) else {
+        return;
+    };
+    let prefix = data.get("assetPrefix").and_then(Value::as_str).unwrap_or("");
+    for name in ["_buildManifest.js", "_ssgManifest.js"] {
+        let url = format!("{}/_next/static/{}/{}", prefix.trim_end_matches('/'), build_id, name);
+        collector.push_asset(&url, Extractor::Next);
+    }
+}
+
+/// _buildManifest.js和_ssgManifest.js: 以/开头的字符串为页面路由，static/开头的JS为chunk；
+/// /posts/[id]等动态路由没有对应的实际地址，只作为路由输出
+pub(crate) fn next(collector: &mut Collector, content: &str) {
+    let root = framework_root(collector, "/_next/");
+    for token in tokenize(content) {
+        let Token::Str(value) = token else {
+            continue;
+        };
+        if value.starts_with("static/") && is_js_path(&value) {
+            collector.push_js_url(&format!("{}{}", root, value), Extractor::Next);
+        } else if is_route(&value) && value.contains(['[', ']']) {
+            collector.push_route(value);
+        } else if is_route(&value) {
+            collector.push_url(&value, Extractor::Next);
+        }
+    }
+}
+
+/// Nuxt 3的builds/latest.json和builds/meta/<id>.json，以及Nuxt 2静态部署的manifest.js
+pub(crate) fn nuxt(collector: &mut Collector, content: &str) {
+    let Ok(value) = serde_json::from_str::<Value>(content.trim_start_matches('\u{feff}')) else {
+        // __NUXT_JSONP__("manifest.js", {routes: [...]})
+        for token in tokenize(content) {
+            if let Token::Str(value) = &token {
+                if is_route(value) {
+                    collector.push_url(value, Extractor::Nuxt);
+                }
+            }
+        }
+        return;
+    };
+
+    let routes = value.get("prerendered").and_then(Value::as_array).into_iter().flatten()
+        .filter_map(Value::as_str)
+        .chain(value.pointer("/matcher/static").and_then(Value::as_object).into_iter().flat_map(|m| m.keys().map(String::as_str)));
+    let mut found = false;
+    for route in routes.filter(|route| is_route(route)) {
+        collector.push_url(route, Extractor::Nuxt);
+        found = true;
+    }
+
+    if let (false, Some(id)) = (found, value.get("id").and_then(Value::as_str)) {
+        let root = framework_root(collector, "/_nuxt/");
+        collector.push_asset(&format!("{}builds/meta/{}.json", root, id), Extractor::Nuxt);
+    }
+}
+
+/// Vite的manifest.json: 每项的file为输出文件，src为对应的源文件
+pub(crate) fn vite(collector: &mut Collector, content: &str) {
+    let Ok(Value::Object(entries)) = serde_json::from_str::<Value>(content.trim_start_matches('\u{feff}')) else {
+        json::extract(collector, content);
+        return;
+    };
+    // 文件路径相对于输出目录，即清单所在目录(Vite 5起为.vite的上级目录)
+    let page = collector.page_url().split(['?', '#']).next().unwrap_or("").to_string();
+    let dir = page.rsplit_once('/').map_or(page.as_str(), |(dir, _)| dir);
+    let root = dir.strip_suffix("/.vite").unwrap_or(dir).to_string();
+
+    for (key, entry) in &entries {
+        if let Some(file) = entry.get("file").and_then(Value::as_str) {
+            let url = format!("{}/{}", root, file);
+            if is_js_path(file) {
+                collector.push_js_url(&url, Extractor::Vite);
+            } else {
+                collector.push_url(&url, Extractor::Vite);
+            }
+        }
+        // 以_开头的为共享chunk，没有源文件
+        let src = entry.get("src").and_then(Value::as_str).unwrap_or(key);
+        if !src.starts_with('_') {
+            collector.push_source(src.to_string());
+        }
+    }
+}
+
+/// 页面路由，排除_app、_error等内部页面
+fn is_route(value: &str) -> bool {
+    value.starts_with('/')
+        && !value.starts_with("/_")
+        && !value.starts_with("//")
+        && !value.contains(char::is_whitespace)
+}
+
+/// 清单所在URL中框架目录(如/_next/)及之前的部分，找不到时使用站点根目录下的该目录
+fn framework_root(collector: &Collector, dir: &str) -> String {
+    let page = collector.page_url();
+    match page.find(dir) {
+        Some(i) => page[..i + dir.len()].to_string(),
+        None => collector.resolve(dir).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::extract::{extract, ExtractOptions};
+    use crate::rules::RuleEngine;
+
+    #[test]
+    fn reports_dynamic_next_routes() {
+        let rules = RuleEngine::compile(&Config::default()).unwrap();
+        let options = ExtractOptions { mode: 2, ..Default::default() };
+        let content = r#"self.__BUILD_MANIFEST={"/about":["static/chunks/pages/about-1a2b.js"],"/posts/[id]":["static/chunks/pages/posts/[id]-3c4d.js"]};"#;
+        let url = "http://example.com/_next/static/abc/_buildManifest.js";
+        let extracted = extract(&rules, &options, "application/javascript", url, content);
+        let urls: Vec<&str> = extracted.urls.iter().map(|link| link.url.as_str()).collect();
+        assert_eq!(urls, ["http://example.com/about"]);
+        assert_eq!(extracted.routes, ["/posts/[id]"]);
+    }
+}
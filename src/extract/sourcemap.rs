@@ -0,0 +1,56 @@
+use serde::Deserialize;
+
+use super::{css, html, js, json, Collector};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceMap {
+    #[serde(default)]
+    source_root: Option<String>,
+    #[serde(default)]
+    sources: Vec<Option<String>>,
+    #[serde(default)]
+    sources_content: Vec<Option<String>>,
+}
+
+/// 内容是否为source map
+pub(crate) fn is_source_map(content: &str) -> bool {
+    content.contains("\"mappings\"") && content.contains("\"sources\"")
+}
+
+/// 记录还原出的源文件路径，并按源文件类型提取sourcesContent中的原始代码
+pub(crate) fn extract(collector: &mut Collector, content: &str) {
+    let Ok(map) = serde_json::from_str::<SourceMap>(content.trim_start_matches('\u{feff}')) else {
+        json::extract(collector, content);
+        return;
+    };
+    let root = map.source_root.unwrap_or_default();
+
+    for (i, source) in map.sources.iter().enumerate() {
+        let Some(source) = source else {
+            continue;
+        };
+        let path = if root.is_empty() {
+            source.clone()
+        } else {
+            format!("{}/{}", root.trim_end_matches('/'), source.trim_start_matches('/'))
+        };
+        collector.push_source(path);
+
+        // 第三方依赖中的地址多为文档和示例，只提取项目自身的代码
+        if source.contains("node_modules/") {
+            continue;
+        }
+        let Some(Some(code)) = map.sources_content.get(i) else {
+            continue;
+        };
+        let name = source.split(['?', '#']).next().unwrap_or("").to_ascii_lowercase();
+        match name.rsplit_once('.').map_or("", |(_, ext)| ext) {
+            "css" | "scss" | "sass" | "less" | "styl" => css::extract(collector, code),
+            // 单文件组件的<template>和<script>按HTML处理
+            "vue" | "svelte" | "html" | "htm" => html::extract(collector, code),
+            _ => js::extract(collector, code),
+        }
+        collector.scan_sensitive(code);
+    }
+}
@@ -0,0 +1,243 @@
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::lexer::{pair_brackets, split_items, tokenize, Token};
+use super::{Collector, Extractor};
+
+/// <script type="application/json">中的数据，name为根节点在路径中的名称
+pub(crate) fn json(collector: &mut Collector, name: &str, content: &str) {
+    match serde_json::from_str::<Value>(content.trim().trim_start_matches('\u{feff}')) {
+        Ok(value) => walk(collector, &value, name, None),
+        Err(_) => collector.scan(content, Extractor::State),
+    }
+}
+
+/// 内联脚本中window.__INITIAL_STATE__ = {...}、window.__NUXT__ = (function(a){return {...}}(...))
+/// 等形式的状态数据，转换为JSON后按路径查找
+pub(crate) fn script(collector: &mut Collector, content: &str) {
+    // 只在可能包含状态数据的脚本上做词法分析
+    if !content.contains("__") {
+        return;
+    }
+    let tokens = tokenize(content);
+    let literal = Literal::new(&tokens);
+
+    for (i, token) in tokens.iter().enumerate() {
+        let Token::Ident(name) = token else {
+            continue;
+        };
+        if name.len() <= 4 || !name.starts_with("__") || !name.ends_with("__") || !literal.is_punct(i + 1, "=") {
+            continue;
+        }
+        // window.__X__、self.__X__或直接赋值
+        let root = match i.checked_sub(2).map(|j| &tokens[j]) {
+            Some(Token::Ident(object)) if literal.is_punct(i - 1, ".") => format!("{}.{}", object, name),
+            _ if literal.is_punct(i.wrapping_sub(1), ".") => continue,
+            _ => name.clone(),
+        };
+        if let Some(value) = literal.value_at(i + 2) {
+            walk(collector, &value, &root, None);
+        }
+    }
+}
+
+/// 遍历JSON，字符串中的URL和敏感信息连同其路径一起记录
+fn walk(collector: &mut Collector, value: &Value, path: &str, key: Option<&str>) {
+    match value {
+        Value::String(s) => {
+            collector.scan_at(s, Extractor::State, Some(path));
+            // 按"键: 值"匹配敏感信息规则，如apiKey: "..."
+            let text = match key {
+                Some(key) => format!("{}: \"{}\"", key, s),
+                None => s.clone(),
+            };
+            collector.scan_sensitive_at(&text, Some(path));
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                walk(collector, item, &format!("{}[{}]", path, i), None);
+            }
+        }
+        Value::Object(map) => {
+            for (k, item) in map {
+                let child = if !k.is_empty() && k.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+                    format!("{}.{}", path, k)
+                } else {
+                    format!("{}[{:?}]", path, k)
+                };
+                walk(collector, item, &child, Some(k));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 字面量的最大嵌套层数，与serde_json的限制相同
+const MAX_DEPTH: usize = 128;
+
+/// 将JS字面量转换为JSON，无法确定的值记为null
+struct Literal<'t> {
+    tokens: &'t [Token],
+    pairs: Vec<usize>,
+}
+
+impl<'t> Literal<'t> {
+    fn new(tokens: &'t [Token]) -> Self {
+        let (pairs, _) = pair_brackets(tokens);
+        Literal { tokens, pairs }
+    }
+
+    /// 赋值右侧的值: 对象或数组字面量、JSON.parse("...")或返回字面量的立即执行函数
+    fn value_at(&self, start: usize) -> Option<Value> {
+        match self.tokens.get(start)? {
+            Token::Punct("{" | "[") => Some(self.value(start, &HashMap::new(), 0)),
+            Token::Ident(word) if word == "JSON" => match self.value(start, &HashMap::new(), 0) {
+                Value::Null => None,
+                value => Some(value),
+            },
+            Token::Punct("(") => self.iife(start),
+            _ => None,
+        }
+    }
+
+    /// (function(a, b){ return {...} }(x, y))或(function(a, b){...})(x, y)，参数按实参替换
+    fn iife(&self, open: usize) -> Option<Value> {
+        let function = open + 1;
+        if !matches!(self.tokens.get(function), Some(Token::Ident(word)) if word == "function") {
+            return None;
+        }
+        let params_open = function + 1;
+        if !self.is_punct(params_open, "(") {
+            return None;
+        }
+        let params_close = self.pairs[params_open];
+        let body_open = params_close + 1;
+        if !self.is_punct(body_open, "{") {
+            return None;
+        }
+        let body_close = self.pairs[body_open];
+        let args_open = if self.is_punct(body_close + 1, "(") {
+            body_close + 1
+        } else if self.is_punct(body_close + 1, ")") && self.is_punct(body_close + 2, "(") {
+            body_close + 2
+        } else {
+            return None;
+        };
+
+        let params = self.split(params_open);
+        let args = self.split(args_open);
+        let empty = HashMap::new();
+        let env: HashMap<String, Value> = params.iter()
+            .zip(args.iter().map(|arg| self.value(arg.start, &empty, 0)).chain(std::iter::repeat(Value::Null)))
+            .filter_map(|(param, value)| match &self.tokens[param.start] {
+                Token::Ident(name) => Some((name.clone(), value)),
+                _ => None,
+            })
+            .collect();
+
+        // 函数体顶层的return
+        let mut i = body_open + 1;
+        while i < body_close {
+            match &self.tokens[i] {
+                Token::Punct("(" | "[" | "{") => i = self.pairs[i],
+                Token::Ident(word) if word == "return" => return Some(self.value(i + 1, &env, 0)),
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// depth为嵌套层数，超过MAX_DEPTH的部分记为null
+    fn value(&self, i: usize, env: &HashMap<String, Value>, depth: usize) -> Value {
+        let Some(token) = self.tokens.get(i).filter(|_| depth < MAX_DEPTH) else {
+            return Value::Null;
+        };
+        match token {
+            Token::Str(s) => Value::String(s.clone()),
+            Token::Template(template) if template.exprs.is_empty() => Value::String(template.quasis.concat()),
+            Token::Num(n) => number(n, false),
+            // 压缩代码中的!0、!1
+            Token::Punct("!") => match self.tokens.get(i + 1) {
+                Some(Token::Num(n)) => Value::Bool(n == "0"),
+                _ => Value::Null,
+            },
+            Token::Punct("-") => match self.tokens.get(i + 1) {
+                Some(Token::Num(n)) => number(n, true),
+                _ => Value::Null,
+            },
+            Token::Punct("{") => {
+                let mut map = Map::new();
+                for range in self.split(i) {
+                    let key = match &self.tokens[range.start] {
+                        Token::Ident(k) | Token::Str(k) | Token::Num(k) => k.clone(),
+                        _ => continue,
+                    };
+                    let value = if self.is_punct(range.start + 1, ":") {
+                        self.value(range.start + 2, env, depth + 1)
+                    } else if range.len() == 1 {
+                        env.get(&key).cloned().unwrap_or(Value::Null)
+                    } else {
+                        continue;
+                    };
+                    map.insert(key, value);
+                }
+                Value::Object(map)
+            }
+            Token::Punct("[") => Value::Array(self.split(i).into_iter().map(|range| self.value(range.start, env, depth + 1)).collect()),
+            Token::Ident(word) => match word.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "JSON" if self.is_member(i, "parse") && self.is_punct(i + 3, "(") => match self.tokens.get(i + 4) {
+                    Some(Token::Str(s)) => serde_json::from_str(s).unwrap_or(Value::Null),
+                    _ => Value::Null,
+                },
+                name => env.get(name).cloned().unwrap_or(Value::Null),
+            },
+            _ => Value::Null,
+        }
+    }
+
+    /// 括号内以逗号分隔的各项，open为左括号位置
+    fn split(&self, open: usize) -> Vec<Range<usize>> {
+        split_items(self.tokens, &self.pairs, open)
+    }
+
+    fn is_member(&self, i: usize, name: &str) -> bool {
+        self.is_punct(i + 1, ".") && matches!(self.tokens.get(i + 2), Some(Token::Ident(word)) if word == name)
+    }
+
+    fn is_punct(&self, i: usize, punct: &str) -> bool {
+        matches!(self.tokens.get(i), Some(Token::Punct(p)) if *p == punct)
+    }
+}
+
+fn number(literal: &str, negative: bool) -> Value {
+    let Ok(n) = literal.replace('_', "").parse::<f64>() else {
+        return Value::String(literal.to_string());
+    };
+    let n = if negative { -n } else { n };
+    if n.fract() == 0.0 && n.abs() < 9e15 {
+        Value::Number(Number::from(n as i64))
+    } else {
+        Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::extract::{extract, ExtractOptions};
+    use crate::rules::RuleEngine;
+
+    #[test]
+    fn limits_nesting_depth() {
+        let rules = RuleEngine::compile(&Config::default()).unwrap();
+        let options = ExtractOptions { mode: 3, ..Default::default() };
+        let nested = format!("{}\"/api/deep\"{}", "[".repeat(5000), "]".repeat(5000));
+        let html = format!("<script>window.__A__ = {{api: \"/api/user\", deep: {}}};</script>", nested);
+        let extracted = extract(&rules, &options, "text/html", "http://example.com/", &html);
+        assert!(extracted.urls.iter().any(|link| link.url == "http://example.com/api/user"));
+    }
+}
@@ -0,0 +1,20 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+use super::{unescape_entities, Collector, Extractor};
+
+/// 匹配属性值、文本节点和CDATA中的URL，匹配前还原XML实体
+pub(crate) fn extract(collector: &mut Collector, content: &str) {
+    static XML_VALUE: OnceLock<Regex> = OnceLock::new();
+    let re = XML_VALUE.get_or_init(|| {
+        Regex::new(r#"(?s)<!\[CDATA\[(.*?)\]\]>|"([^"]*)"|'([^']*)'|>([^<]+)<"#).expect("valid regex")
+    });
+
+    for cap in re.captures_iter(content) {
+        if let Some(cdata) = cap.get(1) {
+            collector.scan(cdata.as_str(), Extractor::Xml);
+        } else if let Some(value) = cap.get(2).or_else(|| cap.get(3)).or_else(|| cap.get(4)) {
+            collector.scan(&unescape_entities(value.as_str()), Extractor::Xml);
+        }
+    }
+}
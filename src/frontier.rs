@@ -0,0 +1,169 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::crawler::CrawlResult;
+use crate::extract::is_source_map_path;
+use crate::mode::Mode;
+use crate::scope::Scope;
+use crate::state::State;
+use crate::utils::host_of;
+
+/// 待抓取队列中的一项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrontierEntry {
+    pub url: String,
+    /// 页面递归深度(起始目标为0)
+    pub depth: u8,
+    /// JS递归深度(页面为0，JS文件从1开始)
+    pub js_depth: u8,
+    /// 是否由Fuzz生成
+    pub fuzz: bool,
+    /// 起始目标的主机名，用于限制页面递归范围
+    pub root_host: String,
+}
+
+/// 广度优先的抓取队列
+pub struct Frontier {
+    queue: VecDeque<FrontierEntry>,
+    state: Arc<State>,
+    mode: Mode,
+    crawl_mode: u8,
+    fuzz_mode: Option<u8>,
+    url_depth: u8,
+    js_depth: u8,
+    scope: Scope,
+}
+
+impl Frontier {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        state: Arc<State>,
+        mode: Mode,
+        crawl_mode: u8,
+        fuzz_mode: Option<u8>,
+        url_depth: u8,
+        js_depth: u8,
+        scope: Scope,
+    ) -> Self {
+        Frontier {
+            queue: VecDeque::new(),
+            state,
+            mode,
+            crawl_mode,
+            fuzz_mode,
+            url_depth,
+            js_depth,
+            scope,
+        }
+    }
+
+    /// 添加起始目标
+    pub async fn seed(&mut self, url: String) {
+        let root_host = host_of(&url);
+        if !self.scope.contains(&url, &root_host) {
+            self.state.add_out_of_scope_url(url).await;
+            return;
+        }
+        if !self.state.add_visited_url(url.clone()).await {
+            return;
+        }
+        self.queue.push_back(FrontierEntry {
+            url,
+            depth: 0,
+            js_depth: 0,
+            fuzz: false,
+            root_host,
+        });
+    }
+
+    pub fn pop(&mut self) -> Option<FrontierEntry> {
+        self.queue.pop_front()
+    }
+
+    /// 将未能开始的任务放回队首
+    pub fn requeue(&mut self, entry: FrontierEntry) {
+        self.queue.push_front(entry);
+    }
+
+    /// 恢复快照中未完成的任务，这些URL已记录在已访问集合中
+    pub fn restore(&mut self, entries: Vec<FrontierEntry>) {
+        self.queue.extend(entries);
+    }
+
+    /// 队列中尚未开始的任务
+    pub fn pending(&self) -> Vec<FrontierEntry> {
+        self.queue.iter().cloned().collect()
+    }
+
+    /// 将抓取结果中发现的URL、JS和Fuzz路径加入队列
+    pub async fn expand(&mut self, entry: &FrontierEntry, result: &CrawlResult) -> Result<()> {
+        // Fuzz结果不再继续生成Fuzz路径
+        let fuzz_mode = if entry.fuzz { None } else { self.fuzz_mode };
+        let discovered = self.mode.process(result, self.crawl_mode, fuzz_mode).await?;
+
+        // 先处理JS，同一URL同时出现在页面URL中时按JS抓取；
+        // source map属于发现它的脚本，不增加JS深度
+        for js_url in discovered.js_urls {
+            let js_depth = if is_source_map_path(&js_url) {
+                entry.js_depth.max(1)
+            } else {
+                entry.js_depth.saturating_add(1)
+            };
+            if js_depth > self.js_depth {
+                continue;
+            }
+            if !self.scope.contains(&js_url, &entry.root_host) {
+                self.state.add_out_of_scope_url(js_url).await;
+                continue;
+            }
+            if self.state.add_visited_url(js_url.clone()).await {
+                self.queue.push_back(FrontierEntry {
+                    url: js_url,
+                    depth: entry.depth,
+                    js_depth,
+                    fuzz: false,
+                    root_host: entry.root_host.clone(),
+                });
+            }
+        }
+
+        let depth = entry.depth.saturating_add(1);
+        if depth <= self.url_depth {
+            for url in discovered.urls {
+                if !self.scope.contains(&url, &entry.root_host) {
+                    self.state.add_out_of_scope_url(url).await;
+                    continue;
+                }
+                if self.state.add_visited_url(url.clone()).await {
+                    self.queue.push_back(FrontierEntry {
+                        url,
+                        depth,
+                        js_depth: 0,
+                        fuzz: false,
+                        root_host: entry.root_host.clone(),
+                    });
+                }
+            }
+        }
+
+        for fuzz_url in discovered.fuzz_urls {
+            if !self.scope.contains(&fuzz_url, &entry.root_host) {
+                self.state.add_out_of_scope_url(fuzz_url).await;
+                continue;
+            }
+            if self.state.add_visited_url(fuzz_url.clone()).await {
+                self.queue.push_back(FrontierEntry {
+                    url: fuzz_url,
+                    depth: entry.depth,
+                    js_depth: 0,
+                    fuzz: true,
+                    root_host: entry.root_host.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use url::Url;
 
 use crate::crawler::CrawlResult;
 use crate::config::Config;
+use crate::utils::join_fuzz_path;
 
 pub struct JsFuzzer {
     config: Config,
@@ -16,27 +17,26 @@ impl JsFuzzer {
 
     pub async fn fuzz(&self, results: &[CrawlResult]) -> Result<Vec<String>> {
         let mut js_paths = Vec::new();
+        let domain_re = Regex::new(r"(https?://[^/]+)/")?;
 
         // Extract base paths from JS URLs
         for result in results {
-            for js_url in &result.js_urls {
+            for js_url in result.js_urls.iter().filter(|link| !link.unresolved).map(|link| &link.url) {
                 // Extract path up to last directory
                 if let Ok(url) = Url::parse(js_url) {
                     if let Some(segments) = url.path_segments() {
                         let path = segments.collect::<Vec<_>>();
                         if path.len() > 1 {
                             let base_path = path[..path.len()-1].join("/");
-                            js_paths.push(format!("{}{}", url.origin().ascii_serialization(), base_path));
+                            js_paths.push(format!("{}/{}", url.origin().ascii_serialization(), base_path));
                         }
                     }
                 }
 
                 // Extract domain base path
-                if let Ok(re) = Regex::new(r"(https?://[^/]+)/") {
-                    if let Some(cap) = re.captures(js_url) {
-                        if let Some(base) = cap.get(1) {
-                            js_paths.push(base.as_str().to_string());
-                        }
+                if let Some(cap) = domain_re.captures(js_url) {
+                    if let Some(base) = cap.get(1) {
+                        js_paths.push(base.as_str().to_string());
                     }
                 }
             }
@@ -50,7 +50,7 @@ impl JsFuzzer {
         let mut fuzz_urls = Vec::new();
         for path in js_paths {
             for fuzz_path in &self.config.js_fuzz_paths {
-                fuzz_urls.push(format!("{}{}", path, fuzz_path));
+                fuzz_urls.push(join_fuzz_path(&path, fuzz_path));
             }
         }
 
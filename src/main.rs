@@ -1,11 +1,29 @@
 use clap::Parser;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 mod banner;
+mod budget;
+mod canonical;
+mod checkpoint;
 mod cli;
 mod config;
+mod cpu_pool;
 mod crawler;
+mod extract;
+mod frontier;
+mod js_fuzz;
+mod mode;
 mod output;
+mod rate_limit;
+mod retry;
+mod rules;
+mod scanner;
+mod scope;
+mod shutdown;
+mod state;
+mod url_fuzz;
 mod utils;
 
 #[derive(Parser, Debug, Clone)]
@@ -51,6 +69,18 @@ pub struct Cli {
     #[arg(long = "max")]
     max_count: Option<usize>,
 
+    /// 最大下载字节数(支持K/M/G)
+    #[arg(long = "max-bytes", value_parser = utils::parse_size)]
+    max_bytes: Option<u64>,
+
+    /// 单个主机最大下载字节数(支持K/M/G)
+    #[arg(long = "max-host-bytes", value_parser = utils::parse_size)]
+    max_host_bytes: Option<u64>,
+
+    /// 最长扫描时间(秒)
+    #[arg(long = "max-time")]
+    max_time: Option<u64>,
+
     /// 结果输出路径
     #[arg(short = 'o', long)]
     output: Option<PathBuf>,
@@ -63,6 +93,10 @@ pub struct Cli {
     #[arg(short = 't', long, default_value = "50")]
     threads: usize,
 
+    /// 内容提取使用的CPU线程数(默认为CPU核数)
+    #[arg(long = "cpu-threads")]
+    cpu_threads: Option<usize>,
+
     /// 超时时间(秒)
     #[arg(long = "time", default_value = "5")]
     timeout: u64,
@@ -74,6 +108,42 @@ pub struct Cli {
     /// 404链接Fuzz模式
     #[arg(short = 'z', long)]
     fuzz: Option<u8>,
+
+    /// JS中没有sourceMappingURL时探测<file>.map
+    #[arg(long = "sourcemap-probe")]
+    sourcemap_probe: bool,
+
+    /// 解码内容中的Base64片段(只保留解码为文本的结果)
+    #[arg(long = "decode-base64")]
+    decode_base64: bool,
+
+    /// 单个主机最大并发数
+    #[arg(long = "host-threads")]
+    host_threads: Option<usize>,
+
+    /// 单个主机每秒最大请求数
+    #[arg(long = "rate")]
+    rate: Option<f64>,
+
+    /// 请求前固定延迟(毫秒)
+    #[arg(long = "delay")]
+    delay: Option<u64>,
+
+    /// 请求前随机延迟上限(毫秒)
+    #[arg(long = "jitter")]
+    jitter: Option<u64>,
+
+    /// 从快照文件继续扫描
+    #[arg(long = "resume")]
+    resume: Option<PathBuf>,
+
+    /// 快照文件路径(默认为输出目录下的checkpoint.json)
+    #[arg(long = "checkpoint")]
+    checkpoint: Option<PathBuf>,
+
+    /// 快照保存间隔(秒)，0为不保存
+    #[arg(long = "checkpoint-interval", default_value = "30")]
+    checkpoint_interval: u64,
 }
 
 #[tokio::main]
@@ -92,19 +162,71 @@ async fn main() -> anyhow::Result<()> {
     cli_runner.validate()?;
 
     // 获取配置
-    let config = if let Some(config_path) = cli_runner.get_config_path() {
+    let mut config = if let Some(config_path) = cli_runner.get_config_path() {
         config::Config::load(&config_path)?
     } else {
         config::Config::default()
     };
 
+    // 命令行限速参数覆盖配置文件
+    if let Some(host_threads) = cli_runner.get_host_threads() {
+        config.rate_limit.host_concurrency = host_threads;
+    }
+    if let Some(rate) = cli_runner.get_rate() {
+        config.rate_limit.requests_per_second = rate;
+    }
+    if let Some(delay) = cli_runner.get_delay() {
+        config.rate_limit.delay_ms = delay;
+    }
+    if let Some(jitter) = cli_runner.get_jitter() {
+        config.rate_limit.jitter_ms = jitter;
+    }
+
+    // 扫描范围，-d作为主机正则加入包含规则
+    if let Some(domain) = cli_runner.get_domain_filter() {
+        config.scope.include.push(scope::ScopeRule {
+            host_regex: Some(domain),
+            ..Default::default()
+        });
+    }
+    let scope = scope::Scope::compile(&config.scope)?;
+
+    // 递归深度
+    let url_depth = config.url_depth;
+    let js_depth = config.js_depth;
+    let canonical = config.canonical.clone();
+
+    // 继续扫描时需与快照一致的选项
+    let scan_options = checkpoint::ScanOptions {
+        mode: cli_runner.get_mode(),
+        fuzz_mode: cli_runner.get_fuzz_mode(),
+        base_url: cli_runner.get_base_url(),
+        url_depth,
+        js_depth,
+        scope: config.scope.clone(),
+        canonical: canonical.clone(),
+    };
+
+    // Fuzz处理器
+    let js_fuzzer = js_fuzz::JsFuzzer::new(config.clone());
+    let url_fuzzer = url_fuzz::UrlFuzzer::new(config.clone());
+
+    // 抓取预算
+    let budget = Arc::new(budget::Budget::new(
+        cli_runner.get_max_count(),
+        cli_runner.get_max_bytes(),
+        cli_runner.get_max_host_bytes(),
+        cli_runner.get_max_time().map(Duration::from_secs),
+    ));
+
     // 创建爬虫实例
     let crawler = crawler::Crawler::new(
         config,
         cli_runner.get_threads(),
+        cli_runner.get_cpu_threads(),
         cli_runner.get_timeout(),
         cli_runner.get_mode(),
-        cli_runner.get_max_count(),
+        budget.clone(),
         cli_runner.get_base_url(),
         cli_runner.get_user_agent(),
         cli_runner.get_cookie(),
@@ -119,56 +241,89 @@ async fn main() -> anyhow::Result<()> {
     let output_path = cli_runner.get_output_path().unwrap_or_else(|| PathBuf::from("output"));
     std::fs::create_dir_all(&output_path)?;
 
+    // 快照保存
+    let checkpointer = (cli_runner.get_checkpoint_interval() > 0).then(|| {
+        checkpoint::Checkpointer::new(
+            cli_runner.get_checkpoint_path().unwrap_or_else(|| output_path.join("checkpoint.json")),
+            Duration::from_secs(cli_runner.get_checkpoint_interval()),
+            scan_options.clone(),
+        )
+    });
+
     // 创建输出处理器
     let output_writer = output::OutputWriter::new(output_path);
 
-    // 存储爬取结果
-    let mut results = Vec::new();
-
     // 解析状态码过滤器
     let status_filter = cli_runner.get_status_filter()
         .map(|s| utils::parse_status_codes(&s))
         .unwrap_or_default();
 
-    // 获取域名过滤器
-    let domain_filter = cli_runner.get_domain_filter();
-
-    // 爬取URL
-    for url in urls {
-        match crawler.crawl(&url, 1).await {
-            Ok(result) => {
-                // 检查状态码和域名是否匹配
-                if !utils::is_status_match(result.status, &status_filter) {
-                    continue;
+    // 读取快照，恢复已访问集合、未完成任务和已有结果
+    let (state, pending, resumed_results) = match cli_runner.get_resume_path() {
+        Some(path) => {
+            let resumed = checkpoint::Checkpoint::load(&path)?;
+            if let Some(saved) = &resumed.options {
+                let changed = saved.diff(&scan_options);
+                if !changed.is_empty() {
+                    anyhow::bail!("参数与快照保存时不一致({})，请使用相同的参数继续扫描", changed.join(", "));
                 }
-
-                if let Some(domain_pattern) = &domain_filter {
-                    if !utils::is_domain_match(&result.url, domain_pattern) {
-                        continue;
-                    }
-                }
-
-                let output_result = output::OutputResult {
-                    url: result.url,
-                    status: result.status,
-                    content_type: result.content_type,
-                    urls: result.urls,
-                    js_urls: result.js_urls,
-                    sensitive_info: result.sensitive_info,
-                };
-                results.push(output_result);
-            }
-            Err(e) => {
-                eprintln!("Error crawling {}: {}", url, e);
             }
+            budget.restore(resumed.usage);
+            let state = state::State::restore(resumed.state, canonical);
+            (state, resumed.pending, resumed.results)
         }
+        None => (state::State::new(canonical), Vec::new(), Vec::new()),
+    };
+
+    // 初始化抓取队列
+    let state = Arc::new(state);
+    let mode = mode::Mode::new(state.clone(), js_fuzzer, url_fuzzer);
+    let mut frontier = frontier::Frontier::new(
+        state.clone(),
+        mode,
+        cli_runner.get_mode(),
+        cli_runner.get_fuzz_mode(),
+        url_depth,
+        js_depth,
+        scope,
+    );
+    frontier.restore(pending);
+    for url in urls {
+        frontier.seed(url).await;
     }
 
+    // 监听中断信号
+    let shutdown = shutdown::Shutdown::listen();
+
+    // 并发爬取URL
+    let scanner = scanner::Scanner::new(
+        crawler,
+        frontier,
+        state.clone(),
+        cli_runner.get_threads(),
+        status_filter,
+        resumed_results,
+        checkpointer,
+        shutdown.clone(),
+    );
+    let (results, summary) = scanner.run().await;
+
     // 输出结果
     output_writer.write_json(&results)?;
     output_writer.write_csv(&results)?;
-    output_writer.write_html(&results)?;
+    output_writer.write_html(&results, &summary)?;
+    output_writer.write_summary(&summary)?;
+    output_writer.write_out_of_scope(&state.get_out_of_scope_urls().await)?;
 
-    println!("扫描完成，共处理 {} 个URL", results.len());
+    if let Some(reason) = &summary.stop_reason {
+        println!("扫描提前结束({})，已保存部分结果", reason);
+    }
+    println!(
+        "扫描完成，共处理 {} 个URL (页面 {} 个, JS {} 个, Fuzz {} 个)",
+        results.len(),
+        state.get_visited_count().await,
+        state.get_js_count().await,
+        state.get_fuzz_count().await,
+    );
     Ok(())
 }
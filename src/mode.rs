@@ -1,17 +1,26 @@
 use anyhow::Result;
+use std::sync::Arc;
 use crate::crawler::CrawlResult;
 use crate::js_fuzz::JsFuzzer;
 use crate::url_fuzz::UrlFuzzer;
 use crate::state::State;
 
+/// 单次抓取结果中新发现、待继续处理的URL
+#[derive(Debug, Default)]
+pub struct Discovered {
+    pub urls: Vec<String>,
+    pub js_urls: Vec<String>,
+    pub fuzz_urls: Vec<String>,
+}
+
 pub struct Mode {
-    state: State,
+    state: Arc<State>,
     js_fuzzer: JsFuzzer,
     url_fuzzer: UrlFuzzer,
 }
 
 impl Mode {
-    pub fn new(state: State, js_fuzzer: JsFuzzer, url_fuzzer: UrlFuzzer) -> Self {
+    pub fn new(state: Arc<State>, js_fuzzer: JsFuzzer, url_fuzzer: UrlFuzzer) -> Self {
         Mode {
             state,
             js_fuzzer,
@@ -19,8 +28,8 @@ impl Mode {
         }
     }
 
-    pub async fn process(&self, result: &CrawlResult, mode: u8, fuzz_mode: Option<u8>) -> Result<Vec<String>> {
-        let mut urls = Vec::new();
+    pub async fn process(&self, result: &CrawlResult, mode: u8, fuzz_mode: Option<u8>) -> Result<Discovered> {
+        let mut discovered = Discovered::default();
 
         // 添加当前URL到已访问集合
         self.state.add_visited_url(result.url.clone()).await;
@@ -29,43 +38,43 @@ impl Mode {
         match mode {
             // 正常模式：只处理页面中的URL
             1 => {
-                for url in &result.urls {
-                    if !self.state.is_visited(url).await {
-                        urls.push(url.clone());
+                for link in &result.urls {
+                    if !link.unresolved && !self.state.is_visited(&link.url).await {
+                        discovered.urls.push(link.url.clone());
                     }
                 }
             }
             // 深入模式：处理页面URL和JS中的URL
             2 => {
                 // 处理页面URL
-                for url in &result.urls {
-                    if !self.state.is_visited(url).await {
-                        urls.push(url.clone());
+                for link in &result.urls {
+                    if !link.unresolved && !self.state.is_visited(&link.url).await {
+                        discovered.urls.push(link.url.clone());
                     }
                 }
 
                 // 处理JS URL
-                for js_url in &result.js_urls {
-                    if !self.state.is_js_visited(js_url).await {
-                        self.state.add_js_url(js_url.clone()).await;
-                        urls.push(js_url.clone());
+                for link in &result.js_urls {
+                    if !link.unresolved && !self.state.is_js_visited(&link.url).await {
+                        self.state.add_js_url(link.url.clone()).await;
+                        discovered.js_urls.push(link.url.clone());
                     }
                 }
             }
             // 安全深入模式：处理页面URL、JS URL和敏感信息
             3 => {
                 // 处理页面URL
-                for url in &result.urls {
-                    if !self.state.is_visited(url).await {
-                        urls.push(url.clone());
+                for link in &result.urls {
+                    if !link.unresolved && !self.state.is_visited(&link.url).await {
+                        discovered.urls.push(link.url.clone());
                     }
                 }
 
                 // 处理JS URL
-                for js_url in &result.js_urls {
-                    if !self.state.is_js_visited(js_url).await {
-                        self.state.add_js_url(js_url.clone()).await;
-                        urls.push(js_url.clone());
+                for link in &result.js_urls {
+                    if !link.unresolved && !self.state.is_js_visited(&link.url).await {
+                        self.state.add_js_url(link.url.clone()).await;
+                        discovered.js_urls.push(link.url.clone());
                     }
                 }
 
@@ -81,53 +90,40 @@ impl Mode {
         if let Some(fuzz) = fuzz_mode {
             match fuzz {
                 // 基础Fuzz：只处理404页面
-                1 => {
-                    if result.status == 404 {
-                        let fuzz_urls = self.url_fuzzer.fuzz(&[result.clone()], &result.url, None).await?;
-                        for url in fuzz_urls {
-                            if !self.state.is_fuzz_visited(&url).await {
-                                self.state.add_fuzz_url(url.clone()).await;
-                                urls.push(url);
-                            }
-                        }
-                    }
+                1 if result.status == 404 => {
+                    let fuzz_urls = self.url_fuzzer.fuzz(std::slice::from_ref(result), &result.url, None).await?;
+                    self.add_fuzz_urls(fuzz_urls, &mut discovered).await;
                 }
                 // JS Fuzz：处理JS文件中的路径
                 2 => {
-                    let js_fuzz_urls = self.js_fuzzer.fuzz(&[result.clone()]).await?;
-                    for url in js_fuzz_urls {
-                        if !self.state.is_fuzz_visited(&url).await {
-                            self.state.add_fuzz_url(url.clone()).await;
-                            urls.push(url);
-                        }
-                    }
+                    let js_fuzz_urls = self.js_fuzzer.fuzz(std::slice::from_ref(result)).await?;
+                    self.add_fuzz_urls(js_fuzz_urls, &mut discovered).await;
                 }
                 // 组合Fuzz：同时进行URL和JS Fuzz
                 3 => {
                     // URL Fuzz
                     if result.status == 404 {
-                        let fuzz_urls = self.url_fuzzer.fuzz(&[result.clone()], &result.url, None).await?;
-                        for url in fuzz_urls {
-                            if !self.state.is_fuzz_visited(&url).await {
-                                self.state.add_fuzz_url(url.clone()).await;
-                                urls.push(url);
-                            }
-                        }
+                        let fuzz_urls = self.url_fuzzer.fuzz(std::slice::from_ref(result), &result.url, None).await?;
+                        self.add_fuzz_urls(fuzz_urls, &mut discovered).await;
                     }
 
                     // JS Fuzz
-                    let js_fuzz_urls = self.js_fuzzer.fuzz(&[result.clone()]).await?;
-                    for url in js_fuzz_urls {
-                        if !self.state.is_fuzz_visited(&url).await {
-                            self.state.add_fuzz_url(url.clone()).await;
-                            urls.push(url);
-                        }
-                    }
+                    let js_fuzz_urls = self.js_fuzzer.fuzz(std::slice::from_ref(result)).await?;
+                    self.add_fuzz_urls(js_fuzz_urls, &mut discovered).await;
                 }
                 _ => {}
             }
         }
 
-        Ok(urls)
+        Ok(discovered)
     }
-}
\ No newline at end of file
+
+    async fn add_fuzz_urls(&self, fuzz_urls: Vec<String>, discovered: &mut Discovered) {
+        for url in fuzz_urls {
+            if !self.state.is_fuzz_visited(&url).await {
+                self.state.add_fuzz_url(url.clone()).await;
+                discovered.fuzz_urls.push(url);
+            }
+        }
+    }
+}
@@ -1,18 +1,48 @@
 use anyhow::Result;
 use handlebars::Handlebars;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
 
-#[derive(Debug, Serialize)]
+use crate::extract::Link;
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OutputResult {
     pub url: String,
     pub status: u16,
     pub content_type: String,
-    pub urls: Vec<String>,
-    pub js_urls: Vec<String>,
+    pub urls: Vec<Link>,
+    pub js_urls: Vec<Link>,
     pub sensitive_info: Vec<String>,
+    /// 从source map中还原出的源文件路径
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+    /// 前端路由中的页面路径
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<String>,
+    /// 是否由Fuzz发现
+    pub fuzz: bool,
+    /// 请求最终失败的原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 扫描概况
+#[derive(Debug, Serialize)]
+pub struct ScanSummary {
+    pub results: usize,
+    pub requests: usize,
+    pub bytes: u64,
+    pub elapsed_secs: u64,
+    /// 扫描提前结束的原因，正常完成时为空
+    pub stop_reason: Option<String>,
+    /// 达到单主机字节预算的主机
+    pub exhausted_hosts: Vec<String>,
+    /// 因主机字节预算用完而跳过的URL数
+    pub skipped: usize,
+    /// 范围外URL数，列表见out_of_scope.txt
+    pub out_of_scope: usize,
 }
 
 pub struct OutputWriter {
@@ -31,17 +61,37 @@ impl OutputWriter {
         Ok(())
     }
 
+    pub fn write_summary(&self, summary: &ScanSummary) -> Result<()> {
+        let json = serde_json::to_string_pretty(summary)?;
+        let mut file = File::create(self.output_path.join("summary.json"))?;
+        file.write_all(json.as_bytes())?;
+        Ok(())
+    }
+
+    /// 范围外的URL，每行一个
+    pub fn write_out_of_scope(&self, urls: &[String]) -> Result<()> {
+        let mut file = File::create(self.output_path.join("out_of_scope.txt"))?;
+        for url in urls {
+            writeln!(file, "{}", url)?;
+        }
+        Ok(())
+    }
+
     pub fn write_csv(&self, results: &[OutputResult]) -> Result<()> {
         let mut wtr = csv::Writer::from_path(self.output_path.join("result.csv"))?;
 
         for result in results {
-            wtr.write_record(&[
+            wtr.write_record([
                 &result.url,
                 &result.status.to_string(),
                 &result.content_type,
-                &result.urls.join(", "),
-                &result.js_urls.join(", "),
+                &join_links(&result.urls),
+                &join_links(&result.js_urls),
                 &result.sensitive_info.join(", "),
+                &result.fuzz.to_string(),
+                result.error.as_deref().unwrap_or(""),
+                &result.sources.join(", "),
+                &result.routes.join(", "),
             ])?;
         }
 
@@ -49,15 +99,19 @@ impl OutputWriter {
         Ok(())
     }
 
-    pub fn write_html(&self, results: &[OutputResult]) -> Result<()> {
+    pub fn write_html(&self, results: &[OutputResult], summary: &ScanSummary) -> Result<()> {
         let mut handlebars = Handlebars::new();
         handlebars.register_helper("join", Box::new(|h: &handlebars::Helper, _: &handlebars::Handlebars, _: &handlebars::Context, _: &mut handlebars::RenderContext, out: &mut dyn handlebars::Output| -> handlebars::HelperResult {
             let param = h.param(0).ok_or(handlebars::RenderError::new("Missing parameter"))?;
             let arr = param.value().as_array().ok_or(handlebars::RenderError::new("First parameter must be an array"))?;
             let delimiter = h.param(1).map_or(", ", |v| v.value().as_str().unwrap_or(", "));
             
+            // 提取出的URL与CSV中的格式相同
             let strings: Vec<String> = arr.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .filter_map(|v| match serde_json::from_value::<Link>(v.clone()) {
+                    Ok(link) => Some(describe_link(&link)),
+                    Err(_) => v.as_str().map(|s| s.to_string()),
+                })
                 .collect();
             
             out.write(&strings.join(delimiter))?;
@@ -80,14 +134,21 @@ impl OutputWriter {
 </head>
 <body>
     <h1>URLFinder Scan Results</h1>
+    <p>Requests: {{summary.requests}}, Bytes: {{summary.bytes}}, Elapsed: {{summary.elapsed_secs}}s, Out of scope: {{summary.out_of_scope}}</p>
+    {{#if summary.stop_reason}}<p><strong>Scan stopped early: {{summary.stop_reason}}</strong></p>{{/if}}
+    {{#if summary.exhausted_hosts}}<p>Hosts over byte budget: {{join summary.exhausted_hosts ", "}}</p>{{/if}}
     <table>
         <tr>
             <th>URL</th>
             <th>Status</th>
             <th>Content Type</th>
             <th>Found URLs</th>
+            <th>Routes</th>
             <th>JS URLs</th>
             <th>Sensitive Info</th>
+            <th>Source Files</th>
+            <th>Source</th>
+            <th>Error</th>
         </tr>
         {{#each results}}
         <tr>
@@ -95,8 +156,12 @@ impl OutputWriter {
             <td>{{status}}</td>
             <td>{{content_type}}</td>
             <td>{{join urls ", "}}</td>
+            <td>{{#if routes}}{{join routes ", "}}{{/if}}</td>
             <td>{{join js_urls ", "}}</td>
             <td>{{join sensitive_info ", "}}</td>
+            <td>{{#if sources}}{{join sources ", "}}{{/if}}</td>
+            <td>{{#if fuzz}}fuzz{{else}}crawl{{/if}}</td>
+            <td>{{error}}</td>
         </tr>
         {{/each}}
     </table>
@@ -104,10 +169,29 @@ impl OutputWriter {
 </html>"#,
         )?;
 
-        let html = handlebars.render("report", &serde_json::json!({ "results": results }))?;
+        let html = handlebars.render("report", &serde_json::json!({ "results": results, "summary": summary }))?;
         let mut file = File::create(self.output_path.join("result.html"))?;
         file.write_all(html.as_bytes())?;
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+fn join_links(links: &[Link]) -> String {
+    links.iter().map(describe_link).collect::<Vec<_>>().join(", ")
+}
+
+/// 提取出的URL显示为"[方法] url (来源[, base: 基础地址][, at: JSON路径])"
+fn describe_link(link: &Link) -> String {
+    let mut detail = link.source.to_string();
+    if let Some(base) = &link.base {
+        detail.push_str(&format!(", base: {}", base));
+    }
+    if let Some(path) = &link.path {
+        detail.push_str(&format!(", at: {}", path));
+    }
+    match &link.method {
+        Some(method) => format!("{} {} ({})", method, link.url, detail),
+        None => format!("{} ({})", link.url, detail),
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::Result;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::Instant;
+
+use crate::config::RateLimit;
+
+/// 主机当前的并发状态
+#[derive(Debug)]
+struct GateState {
+    in_flight: usize,
+    /// 当前允许的并发数，被限流时减半，恢复正常后逐步回升
+    limit: usize,
+    /// 连续正常响应的次数
+    healthy: usize,
+}
+
+/// 单个主机的限速状态
+#[derive(Debug)]
+struct HostSlot {
+    gate: StdMutex<GateState>,
+    released: Notify,
+    /// 下一个请求最早可发出的时间
+    next_request: Mutex<Instant>,
+}
+
+/// 主机级别的请求限速器
+#[derive(Debug)]
+pub struct HostLimiter {
+    config: RateLimit,
+    /// 单个主机的并发上限
+    max_concurrency: usize,
+    hosts: Mutex<HashMap<String, Arc<HostSlot>>>,
+}
+
+/// 持有期间占用主机的一个并发名额
+pub struct HostPermit {
+    slot: Arc<HostSlot>,
+}
+
+impl Drop for HostPermit {
+    fn drop(&mut self) {
+        if let Ok(mut gate) = self.slot.gate.lock() {
+            gate.in_flight = gate.in_flight.saturating_sub(1);
+        }
+        self.slot.released.notify_waiters();
+    }
+}
+
+impl HostLimiter {
+    /// 未配置主机并发上限时，以全局线程数作为上限
+    pub fn new(config: RateLimit, threads: usize) -> Self {
+        let max_concurrency = if config.host_concurrency > 0 {
+            config.host_concurrency
+        } else {
+            threads.max(1)
+        };
+        HostLimiter {
+            config,
+            max_concurrency,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 等待主机的并发名额和请求间隔，返回的许可需在请求结束后释放
+    pub async fn acquire(&self, host: &str) -> Result<HostPermit> {
+        let slot = self.slot(host).await;
+
+        loop {
+            let released = slot.released.notified();
+            {
+                let mut gate = slot.gate.lock().map_err(|_| anyhow::anyhow!("host gate poisoned"))?;
+                if gate.in_flight < gate.limit {
+                    gate.in_flight += 1;
+                    break;
+                }
+            }
+            released.await;
+        }
+        let permit = HostPermit { slot: slot.clone() };
+
+        let mut wait = Duration::ZERO;
+
+        // 按每秒请求数预约发送时间，被限流后的等待时间也记录在这里
+        {
+            let mut next_request = slot.next_request.lock().await;
+            let now = Instant::now();
+            let scheduled = (*next_request).max(now);
+            if self.config.requests_per_second > 0.0 {
+                let interval = Duration::from_secs_f64(1.0 / self.config.requests_per_second);
+                *next_request = scheduled + interval;
+            }
+            wait += scheduled - now;
+        }
+
+        // 固定延迟和随机抖动
+        wait += Duration::from_millis(self.config.delay_ms);
+        if self.config.jitter_ms > 0 {
+            wait += Duration::from_millis(rand::thread_rng().gen_range(0..=self.config.jitter_ms));
+        }
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+
+        Ok(permit)
+    }
+
+    /// 主机返回限流响应：并发数减半，并在等待时间内暂停该主机的所有请求
+    pub async fn throttle(&self, host: &str, wait: Duration) {
+        let slot = self.slot(host).await;
+        if let Ok(mut gate) = slot.gate.lock() {
+            gate.limit = (gate.limit / 2).max(1);
+            gate.healthy = 0;
+        }
+
+        let mut next_request = slot.next_request.lock().await;
+        let resume = Instant::now() + wait;
+        if *next_request < resume {
+            *next_request = resume;
+        }
+    }
+
+    /// 主机正常响应：连续正常的次数达到当前并发数后，并发数加一
+    pub async fn recover(&self, host: &str) {
+        let slot = self.slot(host).await;
+        let raised = match slot.gate.lock() {
+            Ok(mut gate) if gate.limit < self.max_concurrency => {
+                gate.healthy += 1;
+                if gate.healthy >= gate.limit {
+                    gate.limit += 1;
+                    gate.healthy = 0;
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        };
+        if raised {
+            slot.released.notify_waiters();
+        }
+    }
+
+    async fn slot(&self, host: &str) -> Arc<HostSlot> {
+        let mut hosts = self.hosts.lock().await;
+        hosts
+            .entry(host.to_string())
+            .or_insert_with(|| {
+                Arc::new(HostSlot {
+                    gate: StdMutex::new(GateState {
+                        in_flight: 0,
+                        limit: self.max_concurrency,
+                        healthy: 0,
+                    }),
+                    released: Notify::new(),
+                    next_request: Mutex::new(Instant::now()),
+                })
+            })
+            .clone()
+    }
+}
+
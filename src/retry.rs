@@ -0,0 +1,62 @@
+use std::error::Error as StdError;
+use std::io::ErrorKind;
+use std::time::{Duration, SystemTime};
+
+use crate::config::RetryPolicy;
+
+/// 第attempt次重试前的指数退避时间
+pub fn backoff_delay(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let factor = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    let delay = policy.backoff_ms.saturating_mul(factor).min(policy.max_backoff_ms);
+    Duration::from_millis(delay)
+}
+
+/// 解析Retry-After头，支持秒数和HTTP日期两种格式
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO))
+}
+
+/// 判断网络错误的类型: timeout, dns, reset, connect
+pub fn classify_error(err: &reqwest::Error) -> Option<&'static str> {
+    if err.is_timeout() {
+        return Some("timeout");
+    }
+
+    let mut source = err.source();
+    while let Some(e) = source {
+        if let Some(io) = e.downcast_ref::<std::io::Error>() {
+            match io.kind() {
+                ErrorKind::TimedOut => return Some("timeout"),
+                ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::BrokenPipe
+                | ErrorKind::UnexpectedEof => return Some("reset"),
+                _ => {}
+            }
+        }
+
+        let message = e.to_string();
+        if message.contains("dns error") || message.contains("failed to lookup address") {
+            return Some("dns");
+        }
+        if message.contains("connection closed before message completed") {
+            return Some("reset");
+        }
+        source = e.source();
+    }
+
+    if err.is_connect() {
+        return Some("connect");
+    }
+    None
+}
+
+/// 按重试策略判断错误是否可重试
+pub fn is_retryable(policy: &RetryPolicy, err: &reqwest::Error) -> bool {
+    classify_error(err).is_some_and(|kind| policy.retry_on.iter().any(|k| k == kind))
+}
@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use regex::{Regex, RegexSet};
+
+use crate::config::Config;
+
+/// 一组预编译的正则规则
+#[derive(Debug)]
+pub struct PatternSet {
+    set: RegexSet,
+    regexes: Vec<Regex>,
+}
+
+impl PatternSet {
+    /// 编译一组规则，出错时指明规则名和序号
+    pub fn compile(name: &str, patterns: &[String]) -> Result<Self> {
+        let regexes = patterns.iter().enumerate()
+            .map(|(i, pattern)| {
+                Regex::new(pattern)
+                    .with_context(|| format!("无效的规则 {}[{}]: {}", name, i, pattern))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let set = RegexSet::new(patterns)
+            .with_context(|| format!("无法编译规则集 {}", name))?;
+        Ok(PatternSet { set, regexes })
+    }
+
+    /// 任一规则匹配即返回true
+    pub fn is_match(&self, text: &str) -> bool {
+        self.set.is_match(text)
+    }
+
+    /// 所有规则的匹配结果，先用RegexSet跳过整体不匹配的规则
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.set.matches(text)
+            .into_iter()
+            .flat_map(move |i| self.regexes[i].find_iter(text).map(|m| m.as_str()))
+    }
+}
+
+/// 配置中所有匹配和过滤规则的预编译结果
+#[derive(Debug)]
+pub struct RuleEngine {
+    pub url_patterns: PatternSet,
+    pub js_patterns: PatternSet,
+    pub sensitive_patterns: PatternSet,
+    pub url_filters: PatternSet,
+    pub js_filters: PatternSet,
+}
+
+impl RuleEngine {
+    pub fn compile(config: &Config) -> Result<Self> {
+        Ok(RuleEngine {
+            url_patterns: PatternSet::compile("url_patterns", &config.url_patterns)?,
+            js_patterns: PatternSet::compile("js_patterns", &config.js_patterns)?,
+            sensitive_patterns: PatternSet::compile("sensitive_patterns", &config.sensitive_patterns)?,
+            url_filters: PatternSet::compile("url_filters", &config.url_filters)?,
+            js_filters: PatternSet::compile("js_filters", &config.js_filters)?,
+        })
+    }
+}
@@ -0,0 +1,295 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::task::{Id, JoinSet};
+use tokio::time::Instant;
+
+use crate::budget::BudgetExhausted;
+use crate::checkpoint::{Checkpoint, Checkpointer};
+use crate::crawler::{CrawlResult, Crawler};
+use crate::extract::Link;
+use crate::frontier::{Frontier, FrontierEntry};
+use crate::output::{OutputResult, ScanSummary};
+use crate::shutdown::Shutdown;
+use crate::state::State;
+use crate::utils;
+
+/// 并发调度抓取队列，并在请求完成时收集结果
+pub struct Scanner {
+    crawler: Arc<Crawler>,
+    frontier: Frontier,
+    state: Arc<State>,
+    /// 同时进行中的抓取任务上限
+    threads: usize,
+    status_filter: Vec<u16>,
+    results: Vec<OutputResult>,
+    /// 进行中的任务，保存快照时一并记录
+    in_flight: HashMap<Id, FrontierEntry>,
+    checkpointer: Option<Checkpointer>,
+    shutdown: Shutdown,
+    /// 提前结束的原因
+    stop_reason: Option<String>,
+    /// 因主机字节预算用完而跳过的URL数
+    skipped: usize,
+}
+
+impl Scanner {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        crawler: Crawler,
+        frontier: Frontier,
+        state: Arc<State>,
+        threads: usize,
+        status_filter: Vec<u16>,
+        results: Vec<OutputResult>,
+        checkpointer: Option<Checkpointer>,
+        shutdown: Shutdown,
+    ) -> Self {
+        Scanner {
+            crawler: Arc::new(crawler),
+            frontier,
+            state,
+            threads: threads.max(1),
+            status_filter,
+            results,
+            in_flight: HashMap::new(),
+            checkpointer,
+            shutdown,
+            stop_reason: None,
+            skipped: 0,
+        }
+    }
+
+    pub async fn run(mut self) -> (Vec<OutputResult>, ScanSummary) {
+        let started = Instant::now();
+        let mut tasks = JoinSet::new();
+
+        loop {
+            // 补充任务直到达到并发上限，收到中断信号或预算用完后不再调度新请求
+            while tasks.len() < self.threads && !self.is_stopping() {
+                let Some(entry) = self.frontier.pop() else {
+                    break;
+                };
+
+                match self.crawler.budget().try_request(&utils::host_of(&entry.url)) {
+                    Ok(()) => {}
+                    // 该主机的字节预算已用完，跳过其余URL
+                    Err(BudgetExhausted::HostBytes) => {
+                        self.skipped += 1;
+                        continue;
+                    }
+                    Err(reason) => {
+                        self.frontier.requeue(entry);
+                        self.stop(reason);
+                        break;
+                    }
+                }
+
+                let crawler = self.crawler.clone();
+                let url = entry.url.clone();
+                let handle = tasks.spawn(async move { crawler.crawl(&url).await });
+                self.in_flight.insert(handle.id(), entry);
+            }
+
+            // 队列为空且没有进行中的任务时结束，到达截止时间时中止进行中的请求
+            let joined = match self.crawler.budget().deadline() {
+                Some(deadline) => tokio::select! {
+                    joined = tasks.join_next_with_id() => joined,
+                    _ = tokio::time::sleep_until(deadline) => {
+                        tasks.abort_all();
+                        for (_, entry) in self.in_flight.drain() {
+                            self.frontier.requeue(entry);
+                        }
+                        self.stop(BudgetExhausted::Time);
+                        break;
+                    }
+                },
+                None => tasks.join_next_with_id().await,
+            };
+            let Some(joined) = joined else {
+                break;
+            };
+
+            match joined {
+                Ok((id, result)) => {
+                    if let Some(entry) = self.in_flight.remove(&id) {
+                        self.handle(entry, result).await;
+                    }
+                }
+                Err(e) => {
+                    self.in_flight.remove(&e.id());
+                    eprintln!("Crawl task failed: {}", e);
+                }
+            }
+
+            // 定期保存快照
+            if self.checkpointer.as_ref().is_some_and(|c| c.is_due()) {
+                self.save_checkpoint().await;
+            }
+        }
+
+        if self.shutdown.is_requested() && self.stop_reason.is_none() {
+            self.stop_reason = Some("interrupted".to_string());
+        }
+
+        if self.stop_reason.is_some() {
+            // 提前结束时保存剩余队列，以便继续扫描
+            if self.checkpointer.is_some() {
+                self.save_checkpoint().await;
+            }
+        } else if let Some(checkpointer) = &self.checkpointer {
+            // 扫描完成后快照不再需要
+            if let Err(e) = checkpointer.remove() {
+                eprintln!("Error removing checkpoint: {}", e);
+            }
+        }
+
+        let budget = self.crawler.budget();
+        let summary = ScanSummary {
+            results: self.results.len(),
+            requests: budget.requests(),
+            bytes: budget.bytes(),
+            elapsed_secs: started.elapsed().as_secs(),
+            out_of_scope: self.state.get_out_of_scope_urls().await.len(),
+            stop_reason: self.stop_reason,
+            exhausted_hosts: budget.exhausted_hosts(),
+            skipped: self.skipped,
+        };
+        (self.results, summary)
+    }
+
+    fn is_stopping(&self) -> bool {
+        self.stop_reason.is_some() || self.shutdown.is_requested()
+    }
+
+    fn stop(&mut self, reason: BudgetExhausted) {
+        if self.stop_reason.is_none() {
+            eprintln!("扫描提前结束: {}", reason);
+            self.stop_reason = Some(reason.to_string());
+        }
+    }
+
+    async fn save_checkpoint(&mut self) {
+        let Some(checkpointer) = &mut self.checkpointer else {
+            return;
+        };
+        let state = self.state.snapshot().await;
+        let mut pending: Vec<FrontierEntry> = self.in_flight.values().cloned().collect();
+        pending.extend(self.frontier.pending());
+
+        // 暂时移出结果，避免每次保存都复制
+        let checkpoint = Checkpoint {
+            state,
+            pending,
+            results: std::mem::take(&mut self.results),
+            options: Some(checkpointer.options().clone()),
+            usage: self.crawler.budget().usage(),
+        };
+        let (checkpoint, result) = checkpointer.save(checkpoint).await;
+        if let Err(e) = result {
+            eprintln!("Error saving checkpoint: {}", e);
+        }
+        self.results = checkpoint.results;
+    }
+
+    async fn handle(&mut self, entry: FrontierEntry, result: Result<CrawlResult>) {
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                // 记录失败的目标和原因
+                let error = if e.chain().count() > 1 {
+                    format!("{}: {}", e, e.root_cause())
+                } else {
+                    e.to_string()
+                };
+                eprintln!("Error crawling {}: {}", entry.url, error);
+                self.results.push(OutputResult {
+                    url: entry.url,
+                    status: 0,
+                    content_type: String::new(),
+                    urls: Vec::new(),
+                    js_urls: Vec::new(),
+                    sensitive_info: Vec::new(),
+                    sources: Vec::new(),
+                    routes: Vec::new(),
+                    fuzz: entry.fuzz,
+                    error: Some(error),
+                });
+                return;
+            }
+        };
+
+        // 将发现的URL、JS和Fuzz路径加入队列
+        if let Err(e) = self.frontier.expand(&entry, &result).await {
+            eprintln!("Error processing {}: {}", entry.url, e);
+        }
+
+        // Fuzz路径不存在时不输出
+        if entry.fuzz && result.status == 404 {
+            return;
+        }
+
+        // 检查状态码是否匹配
+        if !utils::is_status_match(result.status, &self.status_filter) {
+            return;
+        }
+
+        println!("[{}] {}", result.status, result.url);
+
+        self.results.push(OutputResult {
+            url: result.url,
+            status: result.status,
+            content_type: result.content_type,
+            urls: self.dedup_urls(result.urls),
+            js_urls: self.dedup_urls(result.js_urls),
+            sensitive_info: result.sensitive_info,
+            sources: dedup(result.sources),
+            routes: dedup(result.routes),
+            fuzz: entry.fuzz,
+            error: None,
+        });
+    }
+
+    /// 按规范形式去重，保留首次出现的顺序和原始地址；同一地址的不同请求方法分别保留，
+    /// 未知方法的记录合并到已知方法的记录中，基础地址以首个推断出的为准
+    fn dedup_urls(&self, urls: Vec<Link>) -> Vec<Link> {
+        let mut deduped: Vec<Link> = Vec::new();
+        let mut by_url: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for link in urls {
+            let key = self.state.canonicalize(&link.url);
+            let Some(indexes) = by_url.get_mut(&key) else {
+                by_url.insert(key, vec![deduped.len()]);
+                deduped.push(link);
+                continue;
+            };
+            let same = indexes.iter()
+                .copied()
+                .find(|&i| link.method.is_none() || deduped[i].method == link.method);
+            if let Some(i) = same {
+                // 保留推断出的基础地址和JSON路径
+                if deduped[i].base.is_none() {
+                    deduped[i].base = link.base;
+                }
+                if deduped[i].path.is_none() {
+                    deduped[i].path = link.path;
+                }
+                continue;
+            }
+            match indexes.iter().find(|&&i| deduped[i].method.is_none()) {
+                Some(&i) => deduped[i].method = link.method,
+                None => {
+                    indexes.push(deduped.len());
+                    deduped.push(link);
+                }
+            }
+        }
+        deduped
+    }
+}
+
+/// 去除重复项，保留首次出现的顺序
+fn dedup(items: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    items.into_iter().filter(|item| seen.insert(item.clone())).collect()
+}
@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use ipnet::IpNet;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use url::{Host, Url};
+
+/// 扫描范围配置，URL需匹配任一include规则且不匹配任何exclude规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ScopeConfig {
+    /// 包含规则，为空时只允许起始目标所在的主机
+    #[serde(default)]
+    pub include: Vec<ScopeRule>,
+    /// 排除规则
+    #[serde(default)]
+    pub exclude: Vec<ScopeRule>,
+}
+
+/// 单条范围规则，所有已设置的字段都匹配时规则才匹配
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ScopeRule {
+    /// 协议，如http、https
+    #[serde(default)]
+    pub scheme: Option<String>,
+    /// 主机名，支持精确匹配(example.com)和通配子域名(*.example.com，同时匹配example.com)
+    #[serde(default)]
+    pub host: Option<String>,
+    /// 主机名正则
+    #[serde(default)]
+    pub host_regex: Option<String>,
+    /// 端口，未写明端口时按协议默认端口匹配
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// 路径前缀
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    /// IP或CIDR网段，只匹配以IP访问的URL
+    #[serde(default)]
+    pub cidr: Option<String>,
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    scheme: Option<String>,
+    host: Option<String>,
+    host_regex: Option<Regex>,
+    port: Option<u16>,
+    path_prefix: Option<String>,
+    cidr: Option<IpNet>,
+}
+
+/// 编译后的扫描范围
+#[derive(Debug)]
+pub struct Scope {
+    include: Vec<CompiledRule>,
+    exclude: Vec<CompiledRule>,
+}
+
+impl Scope {
+    pub fn compile(config: &ScopeConfig) -> Result<Self> {
+        let include = config.include.iter().enumerate()
+            .map(|(i, rule)| compile_rule(rule).with_context(|| format!("无效的scope.include规则 #{}", i + 1)))
+            .collect::<Result<_>>()?;
+        let exclude = config.exclude.iter().enumerate()
+            .map(|(i, rule)| compile_rule(rule).with_context(|| format!("无效的scope.exclude规则 #{}", i + 1)))
+            .collect::<Result<_>>()?;
+        Ok(Scope { include, exclude })
+    }
+
+    /// 判断URL是否在范围内，没有include规则时只允许root_host
+    pub fn contains(&self, url: &str, root_host: &str) -> bool {
+        let Ok(parsed) = Url::parse(url) else {
+            return false;
+        };
+
+        if self.exclude.iter().any(|rule| rule.matches(&parsed)) {
+            return false;
+        }
+
+        if self.include.is_empty() {
+            parsed.host_str().is_some_and(|host| host.eq_ignore_ascii_case(root_host))
+        } else {
+            self.include.iter().any(|rule| rule.matches(&parsed))
+        }
+    }
+}
+
+impl CompiledRule {
+    fn matches(&self, url: &Url) -> bool {
+        if let Some(scheme) = &self.scheme {
+            if !url.scheme().eq_ignore_ascii_case(scheme) {
+                return false;
+            }
+        }
+
+        let host = url.host_str().unwrap_or("").to_ascii_lowercase();
+
+        if let Some(pattern) = &self.host {
+            let matched = match pattern.strip_prefix("*.") {
+                Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+                None => host == *pattern,
+            };
+            if !matched {
+                return false;
+            }
+        }
+
+        if let Some(re) = &self.host_regex {
+            if !re.is_match(&host) {
+                return false;
+            }
+        }
+
+        if let Some(port) = self.port {
+            if url.port_or_known_default() != Some(port) {
+                return false;
+            }
+        }
+
+        if let Some(prefix) = &self.path_prefix {
+            if !url.path().starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(net) = &self.cidr {
+            let ip = match url.host() {
+                Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+                Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+                _ => return false,
+            };
+            if !net.contains(&ip) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn compile_rule(rule: &ScopeRule) -> Result<CompiledRule> {
+    let host_regex = rule.host_regex.as_deref()
+        .map(|pattern| Regex::new(pattern).with_context(|| format!("host_regex: {}", pattern)))
+        .transpose()?;
+
+    // 单个IP按/32或/128处理
+    let cidr = rule.cidr.as_deref()
+        .map(|cidr| {
+            cidr.parse::<IpNet>()
+                .or_else(|_| cidr.parse::<IpAddr>().map(IpNet::from))
+                .with_context(|| format!("cidr: {}", cidr))
+        })
+        .transpose()?;
+
+    Ok(CompiledRule {
+        scheme: rule.scheme.clone(),
+        host: rule.host.as_ref().map(|h| h.to_ascii_lowercase()),
+        host_regex,
+        port: rule.port,
+        path_prefix: rule.path_prefix.clone(),
+        cidr,
+    })
+}
@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 中断信号状态，第一次信号停止调度新请求，第二次信号立即退出
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    requested: Arc<AtomicBool>,
+}
+
+impl Shutdown {
+    /// 在后台监听SIGINT/SIGTERM
+    pub fn listen() -> Self {
+        let shutdown = Shutdown {
+            requested: Arc::new(AtomicBool::new(false)),
+        };
+
+        let requested = shutdown.requested.clone();
+        tokio::spawn(async move {
+            wait_for_signal().await;
+            eprintln!("收到中断信号，等待进行中的请求完成并保存结果，再次按Ctrl-C强制退出");
+            requested.store(true, Ordering::SeqCst);
+
+            wait_for_signal().await;
+            eprintln!("强制退出");
+            std::process::exit(130);
+        });
+
+        shutdown
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+        }
+        Err(_) => {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
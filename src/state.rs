@@ -1,51 +1,99 @@
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use crate::canonical::{canonicalize, CanonicalConfig};
+
 #[derive(Debug)]
 pub struct State {
     visited_urls: Arc<Mutex<HashSet<String>>>,
     js_urls: Arc<Mutex<HashSet<String>>>,
     fuzz_urls: Arc<Mutex<HashSet<String>>>,
+    /// 按规范形式去重，保存首次出现的原始URL
+    out_of_scope_urls: Arc<Mutex<HashMap<String, String>>>,
+    /// 集合中保存的是规范化后的URL
+    canonical: CanonicalConfig,
+}
+
+/// State中各集合的快照
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub visited_urls: Vec<String>,
+    pub js_urls: Vec<String>,
+    pub fuzz_urls: Vec<String>,
+    #[serde(default)]
+    pub out_of_scope_urls: Vec<String>,
 }
 
 impl State {
-    pub fn new() -> Self {
+    pub fn new(canonical: CanonicalConfig) -> Self {
         State {
             visited_urls: Arc::new(Mutex::new(HashSet::new())),
             js_urls: Arc::new(Mutex::new(HashSet::new())),
             fuzz_urls: Arc::new(Mutex::new(HashSet::new())),
+            out_of_scope_urls: Arc::new(Mutex::new(HashMap::new())),
+            canonical,
         }
     }
 
+    /// URL去重使用的规范形式
+    pub fn canonicalize(&self, url: &str) -> String {
+        canonicalize(url, &self.canonical)
+    }
+
     pub async fn add_visited_url(&self, url: String) -> bool {
+        let key = self.canonicalize(&url);
         let mut urls = self.visited_urls.lock().await;
-        urls.insert(url)
+        urls.insert(key)
     }
 
     pub async fn is_visited(&self, url: &str) -> bool {
+        let key = self.canonicalize(url);
         let urls = self.visited_urls.lock().await;
-        urls.contains(url)
+        urls.contains(&key)
     }
 
     pub async fn add_js_url(&self, url: String) -> bool {
+        let key = self.canonicalize(&url);
         let mut urls = self.js_urls.lock().await;
-        urls.insert(url)
+        urls.insert(key)
     }
 
     pub async fn is_js_visited(&self, url: &str) -> bool {
+        let key = self.canonicalize(url);
         let urls = self.js_urls.lock().await;
-        urls.contains(url)
+        urls.contains(&key)
     }
 
     pub async fn add_fuzz_url(&self, url: String) -> bool {
+        let key = self.canonicalize(&url);
         let mut urls = self.fuzz_urls.lock().await;
-        urls.insert(url)
+        urls.insert(key)
     }
 
     pub async fn is_fuzz_visited(&self, url: &str) -> bool {
+        let key = self.canonicalize(url);
         let urls = self.fuzz_urls.lock().await;
-        urls.contains(url)
+        urls.contains(&key)
+    }
+
+    pub async fn add_out_of_scope_url(&self, url: String) -> bool {
+        let key = self.canonicalize(&url);
+        let mut urls = self.out_of_scope_urls.lock().await;
+        if urls.contains_key(&key) {
+            return false;
+        }
+        urls.insert(key, url);
+        true
+    }
+
+    /// 范围外的URL，按字母排序
+    pub async fn get_out_of_scope_urls(&self) -> Vec<String> {
+        let urls = self.out_of_scope_urls.lock().await;
+        let mut urls: Vec<String> = urls.values().cloned().collect();
+        urls.sort();
+        urls
     }
 
     pub async fn get_visited_count(&self) -> usize {
@@ -62,4 +110,29 @@ impl State {
         let urls = self.fuzz_urls.lock().await;
         urls.len()
     }
-}
\ No newline at end of file
+
+    /// 从快照恢复各集合
+    pub fn restore(snapshot: StateSnapshot, canonical: CanonicalConfig) -> Self {
+        let mut out_of_scope_urls = HashMap::new();
+        for url in snapshot.out_of_scope_urls {
+            out_of_scope_urls.entry(canonicalize(&url, &canonical)).or_insert(url);
+        }
+        State {
+            visited_urls: Arc::new(Mutex::new(snapshot.visited_urls.into_iter().collect())),
+            js_urls: Arc::new(Mutex::new(snapshot.js_urls.into_iter().collect())),
+            fuzz_urls: Arc::new(Mutex::new(snapshot.fuzz_urls.into_iter().collect())),
+            out_of_scope_urls: Arc::new(Mutex::new(out_of_scope_urls)),
+            canonical,
+        }
+    }
+
+    /// 导出各集合
+    pub async fn snapshot(&self) -> StateSnapshot {
+        StateSnapshot {
+            visited_urls: self.visited_urls.lock().await.iter().cloned().collect(),
+            js_urls: self.js_urls.lock().await.iter().cloned().collect(),
+            fuzz_urls: self.fuzz_urls.lock().await.iter().cloned().collect(),
+            out_of_scope_urls: self.out_of_scope_urls.lock().await.values().cloned().collect(),
+        }
+    }
+}
@@ -4,6 +4,7 @@ use url::Url;
 
 use crate::crawler::CrawlResult;
 use crate::config::Config;
+use crate::utils::join_fuzz_path;
 
 pub struct UrlFuzzer {
     config: Config,
@@ -16,6 +17,7 @@ impl UrlFuzzer {
 
     pub async fn fuzz(&self, results: &[CrawlResult], target_url: &str, domain: Option<&str>) -> Result<Vec<String>> {
         let mut paths = Vec::new();
+        let domain_re = Regex::new(r"(https?://[^/]+)/")?;
 
         // Extract base paths from URLs
         for result in results {
@@ -30,17 +32,15 @@ impl UrlFuzzer {
                     let path = segments.collect::<Vec<_>>();
                     if path.len() > 1 {
                         let base_path = path[..path.len()-1].join("/");
-                        paths.push(format!("{}{}", url.origin().ascii_serialization(), base_path));
+                        paths.push(format!("{}/{}", url.origin().ascii_serialization(), base_path));
                     }
                 }
             }
 
             // Extract domain base path
-            if let Ok(re) = Regex::new(r"(https?://[^/]+)/") {
-                if let Some(cap) = re.captures(&result.url) {
-                    if let Some(base) = cap.get(1) {
-                        paths.push(base.as_str().to_string());
-                    }
+            if let Some(cap) = domain_re.captures(&result.url) {
+                if let Some(base) = cap.get(1) {
+                    paths.push(base.as_str().to_string());
                 }
             }
         }
@@ -56,6 +56,13 @@ impl UrlFuzzer {
             }
         };
 
+        // Keep only paths on the target host
+        paths.retain(|path| {
+            Url::parse(path)
+                .map(|url| url.host_str() == Some(host.as_str()))
+                .unwrap_or(false)
+        });
+
         // Remove duplicates
         paths.sort();
         paths.dedup();
@@ -64,10 +71,10 @@ impl UrlFuzzer {
         let mut fuzz_urls = Vec::new();
         for path in paths {
             for fuzz_path in &self.config.url_fuzz_paths {
-                fuzz_urls.push(format!("{}{}", path, fuzz_path));
+                fuzz_urls.push(join_fuzz_path(&path, fuzz_path));
             }
         }
 
         Ok(fuzz_urls)
     }
-}
\ No newline at end of file
+}
@@ -1,37 +1,14 @@
 use anyhow::Result;
-use regex::Regex;
 use url::Url;
 
+/// 按RFC 3986将url解析为相对于base_url的绝对地址，只接受http和https
 pub fn normalize_url(url: &str, base_url: &str) -> Result<String> {
-    if url.starts_with("http://") || url.starts_with("https://") {
-        Ok(url.to_string())
-    } else if url.starts_with("//") {
-        let base = Url::parse(base_url)?;
-        Ok(format!("{}{}", base.scheme(), url))
-    } else if url.starts_with('/') {
-        let base = Url::parse(base_url)?;
-        Ok(format!("{}//{}{}", base.scheme(), base.host_str().unwrap_or(""), url))
-    } else {
-        let base = Url::parse(base_url)?;
-        let path = base.path();
-        let parent = if path.ends_with('/') {
-            path.to_string()
-        } else {
-            format!("{}/", path.rsplit('/').nth(1).unwrap_or(""))
-        };
-        Ok(format!("{}//{}{}{}", base.scheme(), base.host_str().unwrap_or(""), parent, url))
-    }
-}
-
-pub fn is_domain_match(url: &str, domain_pattern: &str) -> bool {
-    if let Ok(parsed_url) = Url::parse(url) {
-        if let Some(host) = parsed_url.host_str() {
-            if let Ok(re) = Regex::new(domain_pattern) {
-                return re.is_match(host);
-            }
-        }
+    let base = Url::parse(base_url)?;
+    let resolved = base.join(url.trim())?;
+    if !matches!(resolved.scheme(), "http" | "https") {
+        anyhow::bail!("unsupported scheme: {}", resolved.scheme());
     }
-    false
+    Ok(resolved.to_string())
 }
 
 pub fn parse_status_codes(status_str: &str) -> Vec<u16> {
@@ -47,4 +24,62 @@ pub fn parse_status_codes(status_str: &str) -> Vec<u16> {
 
 pub fn is_status_match(status: u16, filter_status: &[u16]) -> bool {
     filter_status.is_empty() || filter_status.contains(&status)
-}
\ No newline at end of file
+}
+
+/// 拼接基础路径和Fuzz路径，保证中间只有一个斜杠
+pub fn join_fuzz_path(base: &str, fuzz_path: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), fuzz_path.trim_start_matches('/'))
+}
+
+/// 获取URL的主机名，解析失败时返回空字符串
+pub fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from))
+        .unwrap_or_default()
+}
+
+/// 解析字节数，支持K/M/G后缀(1024进制)
+pub fn parse_size(size: &str) -> Result<u64, String> {
+    let size = size.trim();
+    let (number, unit) = match size.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => size.split_at(i),
+        None => (size, ""),
+    };
+    let number: u64 = number.parse().map_err(|_| format!("无效的大小: {}", size))?;
+    let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1 << 10,
+        "M" | "MB" => 1 << 20,
+        "G" | "GB" => 1 << 30,
+        _ => return Err(format!("无效的大小单位: {}", unit)),
+    };
+    number.checked_mul(multiplier).ok_or_else(|| format!("大小超出范围: {}", size))
+}
+
+/// 解码标准或URL安全的Base64，忽略空白和末尾的填充
+pub fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in input.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            c if c.is_ascii_whitespace() => continue,
+            _ => return None,
+        };
+        // 最多保留未输出的14位
+        buffer = ((buffer << 6) | value as u32) & 0x3fff;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}